@@ -20,11 +20,14 @@
 //! dimension) where the slope of every line is either 0 or undefined. These
 //! are useful for very cheap collision detection.
 
+use matrix::Matrix4;
 use point::{Point, Point2, Point3};
+use ray::Ray3;
+use transform::Transform3;
 use vector::{Vector, Vector2, Vector3};
-use num::{BaseNum};
+use num::{BaseNum, BaseFloat};
 use std::fmt;
-use std::num::{zero, one};
+use std::num::{zero, one, abs, signum};
 
 pub trait Aabb<S: BaseNum, V: Vector<S>, P: Point<S, V>> {
     /// Create a new AABB using two points as opposing corners.
@@ -56,6 +59,61 @@ pub trait Aabb<S: BaseNum, V: Vector<S>, P: Point<S, V>> {
     #[inline]
     fn contains(&self, p: &P) -> bool;
 
+    /// Tests whether a point is contained in the box, inclusive for both the
+    /// min and max corners.
+    #[inline]
+    fn contains_inclusive(&self, p: &P) -> bool {
+        p.sub_p(self.min()).comp_min() >= zero() &&
+        self.max().sub_p(p).comp_min() >= zero()
+    }
+
+    /// Tests whether another AABB lies entirely inside this one, including
+    /// on its boundary.
+    #[inline]
+    fn contains_aabb(&self, other: &Self) -> bool {
+        other.min().sub_p(self.min()).comp_min() >= zero() &&
+        self.max().sub_p(other.max()).comp_min() >= zero()
+    }
+
+    /// Tests whether two AABBs overlap. Boxes that only touch on their
+    /// boundaries are considered to overlap.
+    #[inline]
+    fn overlaps(&self, other: &Self) -> bool {
+        self.max().sub_p(other.min()).comp_min() >= zero() &&
+        other.max().sub_p(self.min()).comp_min() >= zero()
+    }
+
+    /// Returns the smallest AABB that contains both this one and `other`.
+    #[inline]
+    fn union(&self, other: &Self) -> Self {
+        Aabb::new(self.min().min(other.min()), self.max().max(other.max()))
+    }
+
+    /// Returns the AABB where this one and `other` overlap, or `None` if they
+    /// do not overlap.
+    #[inline]
+    fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Aabb::new(self.min().max(other.min()), self.max().min(other.max())))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the point inside or on the boundary of the box that is closest
+    /// to `p`.
+    #[inline]
+    fn closest_point(&self, p: &P) -> P {
+        p.max(self.min()).min(self.max())
+    }
+
+    /// Returns the squared distance from the box to `p`, which is zero if the
+    /// point is inside the box.
+    #[inline]
+    fn distance2(&self, p: &P) -> S {
+        p.sub_p(&self.closest_point(p)).length2()
+    }
+
     /// Returns a new AABB that is grown to include the given point.
     fn grow(&self, p: &P) -> Self {
         let min = self.min().min(p);
@@ -63,6 +121,25 @@ pub trait Aabb<S: BaseNum, V: Vector<S>, P: Point<S, V>> {
         Aabb::new(min, max)
     }
 
+    /// Returns a new AABB that is enlarged by `margin` on every side.
+    #[inline]
+    fn expand(&self, margin: S) -> Self {
+        let m = one::<V>().mul_s(margin);
+        Aabb::new(self.min().add_v(&-m), self.max().add_v(&m))
+    }
+
+    /// Create the smallest AABB that contains all of the points produced by
+    /// an iterator, or `None` if there are no points.
+    fn from_points<I: Iterator<P>>(mut points: I) -> Option<Self> {
+        match points.next() {
+            Some(first) => {
+                let aabb: Self = Aabb::new(first.clone(), first);
+                Some(points.fold(aabb, |aabb, p| aabb.grow(&p)))
+            }
+            None => None,
+        }
+    }
+
     /// Add a vector to every point in the AABB, returning a new AABB.
     fn add_v(&self, v: &V) -> Self {
         Aabb::new(self.min().add_v(v), self.max().add_v(v))
@@ -101,6 +178,36 @@ impl<S: BaseNum> Aabb2<S> {
     }
 }
 
+impl<S: BaseNum> Aabb2<S> {
+    /// The perimeter of the box.
+    #[inline]
+    pub fn perimeter(&self) -> S {
+        let d = self.dim();
+        (d.x + d.y) + (d.x + d.y)
+    }
+
+    /// Split the box into four equal quadrants. Bit `k` of the index of each
+    /// quadrant selects the upper half of the box along axis `k`.
+    pub fn quadrants(&self) -> [Aabb2<S>, ..4] {
+        let c = self.center();
+        let quadrant = |i: uint| {
+            let (x0, x1) = if i & 1 == 0 { (self.min.x, c.x) } else { (c.x, self.max.x) };
+            let (y0, y1) = if i & 2 == 0 { (self.min.y, c.y) } else { (c.y, self.max.y) };
+            Aabb2::new(Point2::new(x0, y0), Point2::new(x1, y1))
+        };
+        [quadrant(0), quadrant(1), quadrant(2), quadrant(3)]
+    }
+}
+
+impl<S: BaseFloat> Aabb2<S> {
+    /// Returns the distance from the box to `p`, which is zero if the point is
+    /// inside the box.
+    #[inline]
+    pub fn distance(&self, p: &Point2<S>) -> S {
+        self.distance2(p).sqrt()
+    }
+}
+
 impl<S: BaseNum> Aabb<S, Vector2<S>, Point2<S>> for Aabb2<S> {
     #[inline]
     fn new(p1: Point2<S>, p2: Point2<S>) -> Aabb2<S> { Aabb2::new(p1, p2) }
@@ -148,6 +255,184 @@ impl<S: BaseNum> Aabb3<S> {
     }
 }
 
+impl<S: BaseNum> Aabb3<S> {
+    /// The total area of the faces of the box.
+    #[inline]
+    pub fn surface_area(&self) -> S {
+        let d = self.dim();
+        let a = d.x * d.y + d.y * d.z + d.z * d.x;
+        a + a
+    }
+
+    /// Split the box into eight equal octants. Bit `k` of the index of each
+    /// octant selects the upper half of the box along axis `k`.
+    pub fn octants(&self) -> [Aabb3<S>, ..8] {
+        let c = self.center();
+        let octant = |i: uint| {
+            let (mut min, mut max) = (self.min, c);
+            for k in range(0u, 3) {
+                if i & (1 << k) != 0 {
+                    min[k] = c[k];
+                    max[k] = self.max[k];
+                }
+            }
+            Aabb3::new(min, max)
+        };
+        [octant(0), octant(1), octant(2), octant(3),
+         octant(4), octant(5), octant(6), octant(7)]
+    }
+}
+
+impl<S: BaseFloat> Aabb3<S> {
+    /// Returns the distance from the box to `p`, which is zero if the point is
+    /// inside the box.
+    #[inline]
+    pub fn distance(&self, p: &Point3<S>) -> S {
+        self.distance2(p).sqrt()
+    }
+
+    /// Returns the outward facing normal of the face of the box that is
+    /// closest to `p`, relative to the size of the box. This is intended for
+    /// finding the face that a point on the surface of the box lies on. If the
+    /// box is flat along an axis, the normal is along that axis.
+    pub fn normal_at(&self, p: &Point3<S>) -> Vector3<S> {
+        let two = one::<S>() + one::<S>();
+        let half = self.dim().div_s(two);
+        let offset = p.sub_p(&self.center());
+        let ratio = |i: uint| {
+            if half[i] > zero() { offset[i] / half[i] }
+            else if offset[i] < zero() { Float::neg_infinity() }
+            else { Float::infinity() }
+        };
+        let d = Vector3::new(ratio(0), ratio(1), ratio(2));
+        let (ax, ay, az) = (abs(d.x), abs(d.y), abs(d.z));
+        if ax >= ay && ax >= az {
+            Vector3::new(signum(d.x), zero(), zero())
+        } else if ay >= az {
+            Vector3::new(zero(), signum(d.y), zero())
+        } else {
+            Vector3::new(zero(), zero(), signum(d.z))
+        }
+    }
+
+    /// Finds the parameter at which a ray first hits the surface of the box,
+    /// along with the outward facing normal of the face that it hits, using
+    /// the slab method. If the origin of the ray is inside the box, this is
+    /// the parameter and the face at which the ray leaves it. A ray with a
+    /// zero direction never hits the box.
+    pub fn intersect_ray(&self, ray: &Ray3<S>) -> Option<(S, Vector3<S>)> {
+        let mut tmin: S = Float::neg_infinity();
+        let mut tmax: S = Float::infinity();
+        let mut near = Vector3::zero();
+        let mut far = Vector3::zero();
+
+        for i in range(0u, 3) {
+            let d = ray.direction[i];
+            if d != zero() {
+                let t1 = (self.min[i] - ray.origin[i]) / d;
+                let t2 = (self.max[i] - ray.origin[i]) / d;
+                // the ray enters through the lower face if it moves upwards
+                let sign = if d > zero() { one::<S>() } else { -one::<S>() };
+                if t1.min(t2) > tmin {
+                    tmin = t1.min(t2);
+                    near = Vector3::zero();
+                    near[i] = -sign;
+                }
+                if t1.max(t2) < tmax {
+                    tmax = t1.max(t2);
+                    far = Vector3::zero();
+                    far[i] = sign;
+                }
+            }
+            // the ray is parallel to the slab, so it must start inside it
+            else if ray.origin[i] < self.min[i] || ray.origin[i] > self.max[i] {
+                return None;
+            }
+        }
+
+        if tmax == Float::infinity() || tmax < tmin || tmax < zero() {
+            None
+        } else if tmin >= zero() {
+            Some((tmin, near))
+        } else {
+            Some((tmax, far))
+        }
+    }
+
+    /// Sweeps the box along `motion`, and finds the time at which it first
+    /// touches another, stationary, box, as a fraction of `motion` between
+    /// `0` and `1`. This is returned along with the unit normal of the face
+    /// of the other box that is hit. If the boxes already overlap, the time is
+    /// zero, and the normal is along the axis on which they overlap the least.
+    pub fn sweep_aabb(&self, motion: &Vector3<S>, other: &Aabb3<S>) -> Option<(S, Vector3<S>)> {
+        let mut normal = Vector3::zero();
+        if self.min.x <= other.max.x && other.min.x <= self.max.x &&
+           self.min.y <= other.max.y && other.min.y <= self.max.y &&
+           self.min.z <= other.max.z && other.min.z <= self.max.z {
+            let d = self.center().sub_p(&other.center());
+            let mut axis = 0u;
+            let mut least = Float::infinity();
+            for i in range(0u, 3) {
+                let overlap = self.max[i].min(other.max[i]) - self.min[i].max(other.min[i]);
+                if overlap < least { least = overlap; axis = i; }
+            }
+            normal[axis] = if d[axis] < zero() { -one::<S>() } else { one() };
+            return Some((zero(), normal));
+        }
+
+        // find the times at which the boxes start and stop overlapping along
+        // each axis, and keep the latest start and the earliest stop
+        let mut t_first = zero::<S>();
+        let mut t_last = one::<S>();
+        for i in range(0u, 3) {
+            let v = motion[i];
+            if v == zero() {
+                if self.min[i] > other.max[i] || other.min[i] > self.max[i] { return None; }
+                continue;
+            }
+            let t0 = (other.min[i] - self.max[i]) / v;
+            let t1 = (other.max[i] - self.min[i]) / v;
+            let (enter, exit) = if v > zero() { (t0, t1) } else { (t1, t0) };
+            if enter > t_first {
+                t_first = enter;
+                normal = Vector3::zero();
+                normal[i] = -signum(v);
+            }
+            if exit < t_last { t_last = exit; }
+            if t_first > t_last { return None; }
+        }
+        Some((t_first, normal))
+    }
+}
+
+impl<S: BaseFloat + 'static> Aabb3<S> {
+    /// Transforms the box by an affine transformation matrix, returning the
+    /// smallest axis-aligned box that contains the result. Each corner of the
+    /// new box is found by summing the smaller or larger of the products of
+    /// each matrix element with the old extents (see _Transforming
+    /// Axis-Aligned Bounding Boxes_, James Arvo, Graphics Gems, 1990).
+    pub fn transform_matrix4(&self, m: &Matrix4<S>) -> Aabb3<S> {
+        let t: Point3<S> = Point::from_vec(&m.w.truncate());
+        let (mut min, mut max) = (t, t);
+        for i in range(0u, 3) {
+            for j in range(0u, 3) {
+                let a = m[j][i] * self.min[j];
+                let b = m[j][i] * self.max[j];
+                min[i] = min[i] + a.min(b);
+                max[i] = max[i] + a.max(b);
+            }
+        }
+        Aabb3::new(min, max)
+    }
+
+    /// Transforms the box, returning the smallest axis-aligned box that
+    /// contains the result.
+    #[inline]
+    pub fn transform<T: Transform3<S>>(&self, t: &T) -> Aabb3<S> {
+        self.transform_matrix4(&t.to_matrix4())
+    }
+}
+
 impl<S: BaseNum> Aabb<S, Vector3<S>, Point3<S>> for Aabb3<S> {
     #[inline]
     fn new(p1: Point3<S>, p2: Point3<S>) -> Aabb3<S> { Aabb3::new(p1, p2) }
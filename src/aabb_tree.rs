@@ -0,0 +1,355 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dynamic AABB trees
+//!
+//! A dynamic AABB tree is a bounding volume hierarchy that can be updated
+//! incrementally as objects are added, removed and moved, making it suitable
+//! as the broadphase of a physics simulation. The boxes stored in the tree are
+//! "fattened" by a margin, so that objects can move a little without the tree
+//! needing to be updated.
+
+use std::cmp::max;
+
+use aabb::{Aabb, Aabb2, Aabb3};
+use num::BaseFloat;
+use point::{Point, Point2, Point3};
+use vector::{Vector, Vector2, Vector3};
+
+/// A node of the tree. Leaves have a height of zero, and free nodes a height
+/// of `-1`.
+struct Node<A, T> {
+    aabb: A,
+    parent: Option<uint>,
+    child1: uint,
+    child2: uint,
+    height: int,
+    payload: Option<T>,
+}
+
+impl<A, T> Node<A, T> {
+    #[inline]
+    fn is_leaf(&self) -> bool { self.height == 0 }
+}
+
+/// A dynamic bounding volume hierarchy, which is kept balanced as objects are
+/// inserted and removed. Each object is identified by the id returned when it
+/// was inserted.
+pub struct AabbTree<S, V, P, A, T> {
+    nodes: Vec<Node<A, T>>,
+    free: Vec<uint>,
+    root: Option<uint>,
+    count: uint,
+    margin: S,
+}
+
+pub type AabbTree2<S, T> = AabbTree<S, Vector2<S>, Point2<S>, Aabb2<S>, T>;
+pub type AabbTree3<S, T> = AabbTree<S, Vector3<S>, Point3<S>, Aabb3<S>, T>;
+
+impl<S: BaseFloat, V: Vector<S>, P: Point<S, V>, A: Aabb<S, V, P> + Clone, T> AabbTree<S, V, P, A, T> {
+    /// Create an empty tree. The boxes of the objects in the tree are
+    /// enlarged by `margin` on every side.
+    pub fn new(margin: S) -> AabbTree<S, V, P, A, T> {
+        AabbTree { nodes: Vec::new(), free: Vec::new(), root: None, count: 0, margin: margin }
+    }
+
+    /// The number of objects in the tree.
+    #[inline]
+    pub fn len(&self) -> uint { self.count }
+
+    /// Returns `true` if the tree contains no objects.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.count == 0 }
+
+    /// Add an object with the bounding box `aabb` to the tree, returning the
+    /// id of the object.
+    pub fn insert(&mut self, aabb: &A, payload: T) -> uint {
+        let fat = aabb.expand(self.margin);
+        let leaf = self.alloc(Node {
+            aabb: fat,
+            parent: None,
+            child1: 0,
+            child2: 0,
+            height: 0,
+            payload: Some(payload),
+        });
+        self.insert_leaf(leaf);
+        self.count += 1;
+        leaf
+    }
+
+    /// Remove an object from the tree, returning its payload. Fails if `id`
+    /// does not refer to an object in the tree.
+    pub fn remove(&mut self, id: uint) -> T {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        self.remove_leaf(id);
+        self.count -= 1;
+        let payload = self.node_mut(id).payload.take().unwrap();
+        self.dealloc(id);
+        payload
+    }
+
+    /// Move an object to the bounding box `aabb`, after it has been displaced
+    /// by `displacement`. If the new box is still inside the fattened box
+    /// stored in the tree, nothing is changed and this returns `false`.
+    /// Otherwise the object is reinserted, with its fattened box also extended
+    /// in the direction of the displacement to anticipate further movement,
+    /// and this returns `true`. Fails if `id` does not refer to an object in
+    /// the tree.
+    pub fn move_object(&mut self, id: uint, aabb: &A, displacement: &V) -> bool {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        if self.nodes[id].aabb.contains_aabb(aabb) {
+            return false;
+        }
+
+        self.remove_leaf(id);
+        let fat = aabb.expand(self.margin);
+        let d: P = Point::from_vec(displacement);
+        let origin: P = Point::origin();
+        let extended = Aabb::new(fat.min().add_v(&d.min(&origin).to_vec()),
+                                 fat.max().add_v(&d.max(&origin).to_vec()));
+        self.node_mut(id).aabb = extended;
+        self.insert_leaf(id);
+        true
+    }
+
+    /// The payload of an object. Fails if `id` does not refer to an object in
+    /// the tree.
+    pub fn get<'a>(&'a self, id: uint) -> &'a T {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        self.nodes[id].payload.as_ref().unwrap()
+    }
+
+    /// The fattened bounding box stored for an object. Fails if `id` does not
+    /// refer to an object in the tree.
+    pub fn fat_aabb<'a>(&'a self, id: uint) -> &'a A {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        &self.nodes[id].aabb
+    }
+
+    /// Finds the ids of the objects whose fattened boxes overlap `aabb`.
+    pub fn query(&self, aabb: &A) -> Vec<uint> {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+        match self.root { Some(root) => stack.push(root), None => () }
+        loop {
+            let i = match stack.pop() { Some(i) => i, None => break };
+            let node = &self.nodes[i];
+            if !node.aabb.overlaps(aabb) { continue; }
+            if node.is_leaf() {
+                found.push(i);
+            } else {
+                stack.push(node.child1);
+                stack.push(node.child2);
+            }
+        }
+        found
+    }
+
+    /// Finds every pair of objects whose fattened boxes overlap. Each pair
+    /// is reported once, with the lower id first.
+    pub fn overlapping_pairs(&self) -> Vec<(uint, uint)> {
+        let mut pairs = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            if !node.is_leaf() { continue; }
+            for j in self.query(&node.aabb).move_iter() {
+                if i < j { pairs.push((i, j)); }
+            }
+        }
+        pairs
+    }
+
+    #[inline]
+    fn is_object(&self, id: uint) -> bool {
+        id < self.nodes.len() && self.nodes[id].is_leaf()
+    }
+
+    #[inline]
+    fn node_mut<'a>(&'a mut self, i: uint) -> &'a mut Node<A, T> {
+        &mut self.nodes.as_mut_slice()[i]
+    }
+
+    fn alloc(&mut self, node: Node<A, T>) -> uint {
+        match self.free.pop() {
+            Some(i) => { *self.node_mut(i) = node; i }
+            None => { self.nodes.push(node); self.nodes.len() - 1 }
+        }
+    }
+
+    fn dealloc(&mut self, i: uint) {
+        {
+            let node = self.node_mut(i);
+            node.height = -1;
+            node.parent = None;
+        }
+        self.free.push(i);
+    }
+
+    fn insert_leaf(&mut self, leaf: uint) {
+        let root = match self.root {
+            Some(root) => root,
+            None => {
+                self.root = Some(leaf);
+                self.node_mut(leaf).parent = None;
+                return;
+            }
+        };
+
+        // descend to the sibling that increases the total cost of the tree the
+        // least, using the sum of the dimensions of the boxes as the cost
+        let leaf_aabb = self.nodes[leaf].aabb.clone();
+        let mut index = root;
+        while !self.nodes[index].is_leaf() {
+            let node = &self.nodes[index];
+            let area = node.aabb.dim().comp_add();
+            let combined = node.aabb.union(&leaf_aabb).dim().comp_add();
+
+            // the cost of making a new parent for this node and the leaf
+            let here = combined + combined;
+            // the cost of pushing the leaf further down the tree
+            let inheritance = (combined - area) + (combined - area);
+            let descend = |child: uint| {
+                let child = &self.nodes[child];
+                let merged = child.aabb.union(&leaf_aabb).dim().comp_add();
+                (if child.is_leaf() { merged } else { merged - child.aabb.dim().comp_add() }) + inheritance
+            };
+            let (cost1, cost2) = (descend(node.child1), descend(node.child2));
+
+            if here < cost1 && here < cost2 { break; }
+            index = if cost1 < cost2 { node.child1 } else { node.child2 };
+        }
+
+        let sibling = index;
+        let old_parent = self.nodes[sibling].parent;
+        let aabb = leaf_aabb.union(&self.nodes[sibling].aabb);
+        let height = self.nodes[sibling].height + 1;
+        let new_parent = self.alloc(Node {
+            aabb: aabb,
+            parent: old_parent,
+            child1: sibling,
+            child2: leaf,
+            height: height,
+            payload: None,
+        });
+        self.node_mut(sibling).parent = Some(new_parent);
+        self.node_mut(leaf).parent = Some(new_parent);
+        self.replace_child(old_parent, sibling, new_parent);
+
+        self.fix_upwards(Some(new_parent));
+    }
+
+    fn remove_leaf(&mut self, leaf: uint) {
+        if self.root == Some(leaf) {
+            self.root = None;
+            return;
+        }
+
+        let parent = self.nodes[leaf].parent.unwrap();
+        let grandparent = self.nodes[parent].parent;
+        let sibling = if self.nodes[parent].child1 == leaf {
+            self.nodes[parent].child2
+        } else {
+            self.nodes[parent].child1
+        };
+
+        // the sibling takes the place of the parent
+        self.replace_child(grandparent, parent, sibling);
+        self.node_mut(sibling).parent = grandparent;
+        self.node_mut(leaf).parent = None;
+        self.dealloc(parent);
+
+        self.fix_upwards(grandparent);
+    }
+
+    /// Replaces `old` with `new` in the children of `parent`, or at the root
+    /// of the tree if there is no parent.
+    fn replace_child(&mut self, parent: Option<uint>, old: uint, new: uint) {
+        match parent {
+            Some(p) => {
+                let node = self.node_mut(p);
+                if node.child1 == old { node.child1 = new; } else { node.child2 = new; }
+            }
+            None => self.root = Some(new),
+        }
+    }
+
+    /// Rebalances the tree and recomputes the boxes and heights of the nodes
+    /// from `index` up to the root.
+    fn fix_upwards(&mut self, mut index: Option<uint>) {
+        loop {
+            let i = match index { Some(i) => self.balance(i), None => break };
+            let (child1, child2) = (self.nodes[i].child1, self.nodes[i].child2);
+            let height = 1 + max(self.nodes[child1].height, self.nodes[child2].height);
+            let aabb = self.nodes[child1].aabb.union(&self.nodes[child2].aabb);
+            let node = self.node_mut(i);
+            node.height = height;
+            node.aabb = aabb;
+            index = node.parent;
+        }
+    }
+
+    /// Performs a rotation at `a` if its subtrees are unbalanced, returning
+    /// the index of the node that takes its place.
+    fn balance(&mut self, a: uint) -> uint {
+        if self.nodes[a].is_leaf() || self.nodes[a].height < 2 {
+            return a;
+        }
+
+        let (b, c) = (self.nodes[a].child1, self.nodes[a].child2);
+        let balance = self.nodes[c].height - self.nodes[b].height;
+
+        if balance > 1 {
+            self.rotate(a, c, b, false)
+        } else if balance < -1 {
+            self.rotate(a, b, c, true)
+        } else {
+            a
+        }
+    }
+
+    /// Rotates the taller child `up` of `a` into its place, with `a` becoming
+    /// the first child of `up`. The taller of the children of `up` stays with
+    /// it, and the shorter one is given to `a` to replace `up`. `other` is the
+    /// remaining child of `a`, and `up_is_child1` tells which child of `a` is
+    /// being rotated.
+    fn rotate(&mut self, a: uint, up: uint, other: uint, up_is_child1: bool) -> uint {
+        let (f, g) = (self.nodes[up].child1, self.nodes[up].child2);
+        let (keep, give) = if self.nodes[f].height > self.nodes[g].height { (f, g) } else { (g, f) };
+
+        let parent = self.nodes[a].parent;
+        self.node_mut(up).child1 = a;
+        self.node_mut(up).child2 = keep;
+        self.node_mut(up).parent = parent;
+        self.node_mut(a).parent = Some(up);
+        self.replace_child(parent, a, up);
+
+        if up_is_child1 { self.node_mut(a).child1 = give; } else { self.node_mut(a).child2 = give; }
+        self.node_mut(give).parent = Some(a);
+
+        let a_aabb = self.nodes[other].aabb.union(&self.nodes[give].aabb);
+        let a_height = 1 + max(self.nodes[other].height, self.nodes[give].height);
+        let up_aabb = a_aabb.union(&self.nodes[keep].aabb);
+        let up_height = 1 + max(a_height, self.nodes[keep].height);
+        {
+            let node = self.node_mut(a);
+            node.aabb = a_aabb;
+            node.height = a_height;
+        }
+        let node = self.node_mut(up);
+        node.aabb = up_aabb;
+        node.height = up_height;
+        up
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generic spatial bounds
+
+use std::num::{zero, cast, abs};
+
+use aabb::{Aabb, Aabb3};
+use num::BaseFloat;
+use obb::Obb3;
+use plane::Plane;
+use point::{Point, Point3};
+use sphere::Sphere;
+use vector::{Vector, EuclideanVector};
+
+/// Spatial relation of a bound with respect to a plane or a volume.
+#[deriving(Clone, PartialEq, Show)]
+pub enum Relation {
+    /// Completely inside
+    In,
+    /// Crosses the boundary
+    Cross,
+    /// Completely outside
+    Out,
+}
+
+/// Generic bound, which can be tested against the half-spaces of planes.
+pub trait Bound<S: BaseFloat> {
+    /// Classify the spatial relation with a plane. A bound is `In` if it lies
+    /// entirely on the side of the plane that its normal points towards.
+    fn relate_plane(&self, plane: &Plane<S>) -> Relation;
+}
+
+impl<S: BaseFloat> Bound<S> for Point3<S> {
+    fn relate_plane(&self, plane: &Plane<S>) -> Relation {
+        let dist = self.dot(&plane.n) + plane.d;
+        if dist < zero() { Out } else { In }
+    }
+}
+
+impl<S: BaseFloat> Bound<S> for Sphere<S> {
+    fn relate_plane(&self, plane: &Plane<S>) -> Relation {
+        // the normal need not be of unit length, so scale the radius by it
+        // rather than normalizing the distance
+        let r = self.radius * plane.n.length();
+        let dist = self.center.dot(&plane.n) + plane.d;
+        if dist < -r { Out }
+        else if dist > r { In }
+        else { Cross }
+    }
+}
+
+impl<S: BaseFloat> Bound<S> for Aabb3<S> {
+    fn relate_plane(&self, plane: &Plane<S>) -> Relation {
+        // project the half extents of the box onto the plane normal
+        let half = self.dim().div_s(cast(2i).unwrap());
+        let r = half.x * abs(plane.n.x) +
+                half.y * abs(plane.n.y) +
+                half.z * abs(plane.n.z);
+        let dist = self.center().dot(&plane.n) + plane.d;
+        if dist < -r { Out }
+        else if dist > r { In }
+        else { Cross }
+    }
+}
+
+impl<S: BaseFloat + 'static> Bound<S> for Obb3<S> {
+    fn relate_plane(&self, plane: &Plane<S>) -> Relation {
+        // project the half extents of the box onto the plane normal
+        let axes = self.axes();
+        let r = self.extents.x * abs(plane.n.dot(&axes[0])) +
+                self.extents.y * abs(plane.n.dot(&axes[1])) +
+                self.extents.z * abs(plane.n.dot(&axes[2]));
+        let dist = self.center.dot(&plane.n) + plane.d;
+        if dist < -r { Out }
+        else if dist > r { In }
+        else { Cross }
+    }
+}
@@ -0,0 +1,373 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounding volume hierarchies
+//!
+//! A BVH is a binary tree of axis-aligned bounding boxes, where each box
+//! encloses the boxes of its children. Objects are stored in the leaves, and
+//! queries only descend into the parts of the tree whose boxes they touch.
+
+use std::cmp::{Less, Equal, Greater};
+use std::num::zero;
+
+use aabb::{Aabb, Aabb3};
+use bound::{In, Cross, Out};
+use frustum::Frustum;
+use intersect::Intersect;
+use num::BaseFloat;
+use point::{Point, Point3};
+use ray::Ray3;
+
+/// The maximum number of objects that are stored in a single leaf.
+static MAX_LEAF_SIZE: uint = 4;
+
+/// The strategy used to divide objects between the children of a node when
+/// building a `Bvh`.
+#[deriving(Clone, PartialEq, Show)]
+pub enum BvhSplit {
+    /// Split the objects at the median of their centers, along the longest
+    /// axis. This is fast to build, but may produce slower trees.
+    MedianSplit,
+    /// Choose the split that minimizes the surface area heuristic. This is
+    /// slower to build, but produces better trees.
+    SahSplit,
+}
+
+/// A node of the tree. Nodes are stored in depth-first order, so the first
+/// child of an interior node directly follows it.
+#[deriving(Clone)]
+struct Node<S> {
+    aabb: Aabb3<S>,
+    /// For a leaf, the index of its first object. For an interior node, the
+    /// index of its second child.
+    offset: uint,
+    /// The number of objects in a leaf, or zero for an interior node.
+    count: uint,
+}
+
+impl<S> Node<S> {
+    #[inline]
+    fn is_leaf(&self) -> bool { self.count > 0 }
+}
+
+/// A bounding volume hierarchy over a set of objects, each of which is
+/// represented by a bounding box and a payload.
+pub struct Bvh<S, T> {
+    nodes: Vec<Node<S>>,
+    items: Vec<(Aabb3<S>, T)>,
+}
+
+impl<S: BaseFloat + 'static, T: Clone> Bvh<S, T> {
+    /// Build a hierarchy over a set of objects and their bounding boxes.
+    pub fn new(items: &[(Aabb3<S>, T)], split: BvhSplit) -> Bvh<S, T> {
+        let mut bvh = Bvh { nodes: Vec::new(), items: Vec::with_capacity(items.len()) };
+        if !items.is_empty() {
+            bvh.build(items, range(0, items.len()).collect(), split);
+        }
+        bvh
+    }
+
+    fn build(&mut self, items: &[(Aabb3<S>, T)], mut indices: Vec<uint>, split: BvhSplit) {
+        let aabb = bounds(items, indices.as_slice());
+        let node = self.nodes.len();
+        self.nodes.push(Node { aabb: aabb, offset: self.items.len(), count: 0 });
+
+        let mid = match split {
+            MedianSplit => split_median(items, &mut indices),
+            SahSplit    => split_sah(items, &mut indices, &aabb),
+        };
+        match mid {
+            Some(mid) => {
+                let right = indices.iter().skip(mid).map(|&i| i).collect();
+                indices.truncate(mid);
+                self.build(items, indices, split);
+                let offset = self.nodes.len();
+                self.nodes.as_mut_slice()[node].offset = offset;
+                self.build(items, right, split);
+            }
+            None => {
+                self.nodes.as_mut_slice()[node].count = indices.len();
+                for &i in indices.iter() {
+                    self.items.push(items[i].clone());
+                }
+            }
+        }
+    }
+
+    /// The number of objects in the hierarchy.
+    #[inline]
+    pub fn len(&self) -> uint { self.items.len() }
+
+    /// Returns `true` if the hierarchy contains no objects.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+
+    /// The bounding box that encloses every object in the hierarchy, or
+    /// `None` if it is empty.
+    #[inline]
+    pub fn aabb(&self) -> Option<Aabb3<S>> {
+        self.nodes.as_slice().head().map(|node| node.aabb)
+    }
+
+    /// Recompute the bounding boxes of the hierarchy after its objects have
+    /// moved. The new bounding box of each object is found by calling `bound`
+    /// on its payload. The structure of the tree is left unchanged, so queries
+    /// will become slower if the objects move far from where they were when
+    /// the hierarchy was built.
+    pub fn refit(&mut self, bound: |&T| -> Aabb3<S>) {
+        for item in self.items.mut_iter() {
+            let (ref mut aabb, ref payload) = *item;
+            *aabb = bound(payload);
+        }
+        // children always come after their parents
+        for i in range(0, self.nodes.len()).rev() {
+            let node = self.nodes[i].clone();
+            let aabb = if node.is_leaf() {
+                bounds_range(self.items.as_slice(), node.offset, node.count)
+            } else {
+                self.nodes[i + 1].aabb.union(&self.nodes[node.offset].aabb)
+            };
+            self.nodes.as_mut_slice()[i].aabb = aabb;
+        }
+    }
+
+    /// Finds the objects whose bounding boxes are hit by a ray. Each object is
+    /// returned along with the ray parameter at which it enters the box, which
+    /// is zero if the box contains the origin of the ray, and the objects are
+    /// sorted from nearest to furthest.
+    pub fn query_ray<'a>(&'a self, ray: &Ray3<S>) -> Vec<(S, &'a T)> {
+        let mut hits = Vec::new();
+        self.traverse(|aabb| (ray.clone(), aabb.clone()).intersection().is_some(), |aabb, payload| {
+            let t = if aabb.contains_inclusive(&ray.origin) {
+                zero()
+            } else {
+                (ray.clone(), aabb.clone()).intersection().unwrap()
+            };
+            hits.push((t, payload));
+        });
+        hits.sort_by(|&(a, _), &(b, _)| if a < b { Less } else if a > b { Greater } else { Equal });
+        hits
+    }
+
+    /// Finds the objects whose bounding boxes overlap a box.
+    pub fn query_aabb<'a>(&'a self, aabb: &Aabb3<S>) -> Vec<&'a T> {
+        let mut found = Vec::new();
+        self.traverse(|b| b.overlaps(aabb), |_, payload| found.push(payload));
+        found
+    }
+
+    /// Finds the objects whose bounding boxes are inside or cross a frustum.
+    pub fn query_frustum<'a>(&'a self, frustum: &Frustum<S>) -> Vec<&'a T> {
+        let mut found = Vec::new();
+        if self.nodes.is_empty() { return found; }
+
+        let mut stack = Vec::new();
+        stack.push((0u, 0b111111u8));
+        loop {
+            let (i, mask) = match stack.pop() { Some(x) => x, None => break };
+            let node = &self.nodes[i];
+            let (relation, crossed) = frustum.contains_masked(&node.aabb, mask);
+            match relation {
+                Out => (),
+                // the node is entirely inside the remaining planes, so
+                // everything below it is too
+                In => self.collect_subtree(i, &mut found),
+                Cross if node.is_leaf() => {
+                    for item in self.items.slice(node.offset, node.offset + node.count).iter() {
+                        let (ref aabb, ref payload) = *item;
+                        let (relation, _) = frustum.contains_masked(aabb, crossed);
+                        if relation != Out { found.push(payload); }
+                    }
+                }
+                Cross => {
+                    stack.push((node.offset, crossed));
+                    stack.push((i + 1, crossed));
+                }
+            }
+        }
+        found
+    }
+
+    /// Finds the object whose bounding box is nearest to `p`, along with the
+    /// distance from `p` to that box. Returns `None` if the hierarchy is
+    /// empty.
+    pub fn nearest<'a>(&'a self, p: &Point3<S>) -> Option<(S, &'a T)> {
+        let mut best: Option<(S, uint)> = None;
+        if self.nodes.is_empty() { return None; }
+
+        let mut stack = Vec::new();
+        stack.push((self.nodes[0].aabb.distance2(p), 0u));
+        loop {
+            let (d, i) = match stack.pop() { Some(x) => x, None => break };
+            match best {
+                Some((best_d, _)) if d >= best_d => continue,
+                _ => (),
+            }
+            let node = &self.nodes[i];
+            if node.is_leaf() {
+                for k in range(node.offset, node.offset + node.count) {
+                    let (ref aabb, _) = self.items[k];
+                    let d = aabb.distance2(p);
+                    match best {
+                        Some((best_d, _)) if d >= best_d => (),
+                        _ => best = Some((d, k)),
+                    }
+                }
+            } else {
+                // visit the nearer child first
+                let (a, b) = (i + 1, node.offset);
+                let (da, db) = (self.nodes[a].aabb.distance2(p), self.nodes[b].aabb.distance2(p));
+                if da <= db {
+                    stack.push((db, b));
+                    stack.push((da, a));
+                } else {
+                    stack.push((da, a));
+                    stack.push((db, b));
+                }
+            }
+        }
+
+        best.map(|(d, k)| {
+            let (_, ref payload) = self.items[k];
+            (d.sqrt(), payload)
+        })
+    }
+
+    /// Calls `found` for each object in a leaf whose bounding box passes
+    /// `test`, only descending into the nodes that pass it.
+    fn traverse<'a>(&'a self, test: |&Aabb3<S>| -> bool, found: |&Aabb3<S>, &'a T|) {
+        if self.nodes.is_empty() { return; }
+
+        let mut stack = Vec::new();
+        stack.push(0u);
+        loop {
+            let i = match stack.pop() { Some(i) => i, None => break };
+            let node = &self.nodes[i];
+            if !test(&node.aabb) { continue; }
+            if node.is_leaf() {
+                for item in self.items.slice(node.offset, node.offset + node.count).iter() {
+                    let (ref aabb, ref payload) = *item;
+                    if test(aabb) { found(aabb, payload); }
+                }
+            } else {
+                stack.push(node.offset);
+                stack.push(i + 1);
+            }
+        }
+    }
+
+    /// Adds every object below a node to `found`.
+    fn collect_subtree<'a>(&'a self, i: uint, found: &mut Vec<&'a T>) {
+        let node = &self.nodes[i];
+        if node.is_leaf() {
+            for item in self.items.slice(node.offset, node.offset + node.count).iter() {
+                let (_, ref payload) = *item;
+                found.push(payload);
+            }
+        } else {
+            self.collect_subtree(i + 1, found);
+            self.collect_subtree(node.offset, found);
+        }
+    }
+}
+
+fn split_median<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], indices: &mut Vec<uint>) -> Option<uint> {
+    if indices.len() <= MAX_LEAF_SIZE { return None; }
+
+    // split along the axis in which the centers are most spread out
+    let mut centers = indices.iter().map(|&i| center(items, i));
+    let first = centers.next().unwrap();
+    let (min, max) = centers.fold((first, first), |(min, max), c| (min.min(&c), max.max(&c)));
+    let d = max.sub_p(&min);
+    let axis = if d.x >= d.y && d.x >= d.z { 0 } else if d.y >= d.z { 1 } else { 2 };
+
+    sort_by_axis(items, indices, axis);
+    Some(indices.len() / 2)
+}
+
+fn split_sah<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], indices: &mut Vec<uint>, aabb: &Aabb3<S>) -> Option<uint> {
+    let n = indices.len();
+    if n <= 1 { return None; }
+
+    // find the split with the lowest cost along each axis, by sweeping over
+    // the objects in the order of their centers
+    let mut best: Option<(S, uint, uint)> = None;
+    let mut right_areas = Vec::from_elem(n, zero::<S>());
+    for axis in range(0u, 3) {
+        sort_by_axis(items, indices, axis);
+
+        let mut right = box_of(items, indices[n - 1]);
+        for k in range(1, n).rev() {
+            right = right.union(&box_of(items, indices[k]));
+            right_areas.as_mut_slice()[k] = right.surface_area();
+        }
+        let mut left = box_of(items, indices[0]);
+        for k in range(1, n) {
+            let cost = left.surface_area() * cast(k) + right_areas[k] * cast(n - k);
+            match best {
+                Some((best_cost, _, _)) if cost >= best_cost => (),
+                _ => best = Some((cost, axis, k)),
+            }
+            left = left.union(&box_of(items, indices[k]));
+        }
+    }
+
+    let (cost, axis, k) = best.unwrap();
+    // only split if it is cheaper than testing every object in a leaf
+    let cheaper = cost < aabb.surface_area() * cast(n);
+    if n <= MAX_LEAF_SIZE && !cheaper {
+        return None;
+    }
+    sort_by_axis(items, indices, axis);
+
+    // when the boxes coincide every split costs the same, so the sweep picks
+    // the first one, which would peel off a single object per level. Halve
+    // the objects instead to keep the depth of the tree logarithmic.
+    if !cheaper || center(items, indices[k - 1])[axis] == center(items, indices[k])[axis] {
+        return split_median(items, indices);
+    }
+    Some(k)
+}
+
+#[inline]
+fn cast<S: BaseFloat>(n: uint) -> S {
+    ::std::num::cast(n).unwrap()
+}
+
+#[inline]
+fn box_of<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], i: uint) -> Aabb3<S> {
+    let (ref aabb, _) = items[i];
+    *aabb
+}
+
+#[inline]
+fn center<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], i: uint) -> Point3<S> {
+    box_of(items, i).center()
+}
+
+fn sort_by_axis<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], indices: &mut Vec<uint>, axis: uint) {
+    indices.sort_by(|&a, &b| {
+        let (ca, cb) = (center(items, a)[axis], center(items, b)[axis]);
+        if ca < cb { Less } else if ca > cb { Greater } else { Equal }
+    });
+}
+
+fn bounds<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], indices: &[uint]) -> Aabb3<S> {
+    indices.iter().skip(1).fold(box_of(items, indices[0]), |aabb, &i| aabb.union(&box_of(items, i)))
+}
+
+fn bounds_range<S: BaseFloat, T>(items: &[(Aabb3<S>, T)], start: uint, count: uint) -> Aabb3<S> {
+    range(start + 1, start + count).fold(box_of(items, start), |aabb, i| aabb.union(&box_of(items, i)))
+}
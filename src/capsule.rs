@@ -0,0 +1,107 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Capsules, or swept spheres
+
+use std::num::{zero, one};
+
+use aabb::Aabb3;
+use line::{Line, Line3};
+use num::BaseFloat;
+use point::{Point, Point3};
+use sphere::Sphere;
+use vector::{Vector, Vector3, EuclideanVector};
+
+/// A capsule, which is the set of points within `radius` of a line segment.
+///
+/// # Fields
+///
+/// - `center`: the midpoint of the segment
+/// - `axis`: the vector from `center` to one end of the segment
+/// - `radius`: the radius of the capsule
+#[deriving(Clone, PartialEq, Encodable, Decodable)]
+pub struct Capsule<S> {
+    pub center: Point3<S>,
+    pub axis: Vector3<S>,
+    pub radius: S,
+}
+
+impl<S: BaseFloat> Capsule<S> {
+    /// Construct a new capsule.
+    #[inline]
+    pub fn new(center: Point3<S>, axis: Vector3<S>, radius: S) -> Capsule<S> {
+        Capsule { center: center, axis: axis, radius: radius }
+    }
+
+    /// Construct a capsule around the segment from `a` to `b`.
+    #[inline]
+    pub fn from_points(a: Point3<S>, b: Point3<S>, radius: S) -> Capsule<S> {
+        let two = one::<S>() + one::<S>();
+        let axis = b.sub_p(&a).div_s(two);
+        Capsule::new(a.add_v(&axis), axis, radius)
+    }
+
+    /// The segment at the core of the capsule.
+    #[inline]
+    pub fn segment(&self) -> Line3<S> {
+        Line::new(self.center.add_v(&-self.axis), self.center.add_v(&self.axis))
+    }
+
+    /// Returns the smallest axis-aligned bounding box that contains the
+    /// capsule.
+    pub fn to_aabb3(&self) -> Aabb3<S> {
+        let (a, b) = (self.center.add_v(&-self.axis), self.center.add_v(&self.axis));
+        let r = Vector3::from_value(self.radius);
+        Aabb3::new(a.min(&b).add_v(&-r), a.max(&b).add_v(&r))
+    }
+
+    /// Returns the smallest sphere centered on the capsule that contains it.
+    #[inline]
+    pub fn to_sphere(&self) -> Sphere<S> {
+        Sphere::new(self.center, self.axis.length() + self.radius)
+    }
+
+    /// Returns the point on the core segment of the capsule that is closest
+    /// to `p`.
+    pub fn closest_point_on_segment(&self, p: &Point3<S>) -> Point3<S> {
+        let d = p.sub_p(&self.center);
+        let len2 = self.axis.length2();
+        let t = if len2 > zero() {
+            (d.dot(&self.axis) / len2).partial_max(-one::<S>()).partial_min(one())
+        } else {
+            zero()
+        };
+        self.center.add_v(&self.axis.mul_s(t))
+    }
+
+    /// Tests whether a point lies inside or on the surface of the capsule.
+    #[inline]
+    pub fn contains(&self, p: &Point3<S>) -> bool {
+        p.sub_p(&self.closest_point_on_segment(p)).length2() <= self.radius * self.radius
+    }
+
+    /// Returns the point inside or on the surface of the capsule that is
+    /// closest to `p`.
+    pub fn closest_point(&self, p: &Point3<S>) -> Point3<S> {
+        let q = self.closest_point_on_segment(p);
+        let d = p.sub_p(&q);
+        let dist2 = d.length2();
+        if dist2 <= self.radius * self.radius {
+            *p
+        } else {
+            q.add_v(&d.mul_s(self.radius / dist2.sqrt()))
+        }
+    }
+}
@@ -44,6 +44,7 @@ pub use array::{Array1, Array2, FixedArray};
 pub use matrix::Matrix;
 pub use matrix::{Matrix2, Matrix3, Matrix4};
 pub use matrix::{ToMatrix2, ToMatrix3, ToMatrix4};
+pub use decomposition::{Lu, Qr};
 pub use quaternion::{Quaternion, ToQuaternion};
 pub use vector::{Vector, EuclideanVector};
 pub use vector::{Vector2, Vector3, Vector4};
@@ -58,23 +59,33 @@ pub use angle::{cot, sec, csc};
 pub use angle::{acos, asin, atan, atan2};
 pub use plane::Plane;
 pub use point::{Point, Point2, Point3};
-pub use line::{Line, Line2, Line3};
+pub use line::{Line, Line2, Line3, LineIntersection, Crossing, Overlap};
 pub use ray::{Ray, Ray2, Ray3};
 pub use rotation::{Rotation, Rotation2, Rotation3};
 pub use rotation::{Basis3, Basis2};
 pub use rotation::{ToBasis2, ToBasis3};
 pub use transform::{Transform, Transform3};
-pub use transform::{Decomposed, AffineMatrix3};
+pub use transform::{Decomposed, NonUniformDecomposed, AffineMatrix3};
+pub use transform::{DecomposeError, PerspectiveMatrix, SingularMatrix, ShearedMatrix, NonUniformScale};
 
 pub use projection::{perspective, frustum, ortho};
 pub use projection::{Projection, PerspectiveFov, Perspective, Ortho};
 
 pub use aabb::{Aabb, Aabb2, Aabb3};
+pub use aabb_tree::{AabbTree, AabbTree2, AabbTree3};
+pub use bound::{Bound, Relation, In, Cross, Out};
+pub use bvh::{Bvh, BvhSplit, MedianSplit, SahSplit};
+pub use capsule::Capsule;
 pub use cylinder::Cylinder;
 pub use frustum::{Frustum, FrustumPoints};
+pub use gjk::{Penetration, gjk_distance, gjk_intersects, epa_penetration};
 pub use intersect::{Intersect, IntersectPoint};
+pub use loose_tree::{LooseTree, Quadtree, Octree};
 pub use obb::{Obb2, Obb3};
+pub use spatial_hash::{SpatialHash2, SpatialHash3};
 pub use sphere::Sphere;
+pub use support::SupportFunction;
+pub use triangle::{Triangle2, Triangle3};
 pub use parallel::Parallel;
 pub use perpendicular::Perpendicular;
 
@@ -86,6 +97,7 @@ pub use num::{PartialOrd, BaseNum, BaseInt, BaseFloat};
 mod array;
 
 mod matrix;
+mod decomposition;
 mod quaternion;
 mod vector;
 
@@ -103,11 +115,20 @@ mod parallel;
 mod perpendicular;
 
 mod aabb;
+mod aabb_tree;
+mod bound;
+mod bvh;
+mod capsule;
 mod cylinder;
 mod frustum;
+mod gjk;
 mod intersect;
+mod loose_tree;
 mod obb;
+mod spatial_hash;
 mod sphere;
+mod support;
+mod triangle;
 
 mod approx;
 mod num;
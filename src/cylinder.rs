@@ -15,12 +15,97 @@
 
 //! Oriented bounding cylinder
 
-use point::Point3;
-use vector::Vector3;
+use std::num::{zero, one};
 
+use aabb::Aabb3;
+use num::BaseFloat;
+use point::{Point, Point3};
+use sphere::Sphere;
+use vector::{Vector, Vector3, EuclideanVector};
+
+/// A finite cylinder with flat caps.
+///
+/// # Fields
+///
+/// - `center`: the point halfway between the centers of the two caps
+/// - `axis`: the vector from `center` to the center of one of the caps, so
+///   that the cylinder is twice as long as `axis`
+/// - `radius`: the radius of the caps
+///
+/// The orientation of the caps is undefined if `axis` is zero, so such a
+/// cylinder is treated as a sphere of `radius` around `center`, in the same
+/// way that a capsule with a degenerate segment is.
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Cylinder<S> {
     pub center: Point3<S>,
     pub axis: Vector3<S>,
     pub radius: S,
 }
+
+impl<S: BaseFloat> Cylinder<S> {
+    /// Construct a new cylinder.
+    #[inline]
+    pub fn new(center: Point3<S>, axis: Vector3<S>, radius: S) -> Cylinder<S> {
+        Cylinder { center: center, axis: axis, radius: radius }
+    }
+
+    /// Returns the smallest axis-aligned bounding box that contains the
+    /// cylinder.
+    pub fn to_aabb3(&self) -> Aabb3<S> {
+        let len2 = self.axis.length2();
+        if len2 == zero() {
+            let r = Vector3::new(self.radius, self.radius, self.radius);
+            return Aabb3::new(self.center.add_v(&-r), self.center.add_v(&r));
+        }
+        // the extent of a cap along each axis depends on how far that axis is
+        // from being perpendicular to the cap
+        let n = self.axis.mul_v(&self.axis).div_s(len2);
+        let e = Vector3::new((one::<S>() - n.x).partial_max(zero()).sqrt(),
+                             (one::<S>() - n.y).partial_max(zero()).sqrt(),
+                             (one::<S>() - n.z).partial_max(zero()).sqrt()).mul_s(self.radius);
+        let (a, b) = (self.center.add_v(&-self.axis), self.center.add_v(&self.axis));
+        Aabb3::new(a.min(&b).add_v(&-e), a.max(&b).add_v(&e))
+    }
+
+    /// Returns the smallest sphere centered on the cylinder that contains it.
+    #[inline]
+    pub fn to_sphere(&self) -> Sphere<S> {
+        Sphere::new(self.center, (self.axis.length2() + self.radius * self.radius).sqrt())
+    }
+
+    /// Tests whether a point lies inside or on the surface of the cylinder.
+    pub fn contains(&self, p: &Point3<S>) -> bool {
+        let d = p.sub_p(&self.center);
+        let len2 = self.axis.length2();
+        if len2 == zero() { return d.length2() <= self.radius * self.radius; }
+        let h = d.dot(&self.axis);
+        // compare the height and radial distance without normalizing the axis
+        h * h <= len2 * len2 &&
+        d.length2() - h * h / len2 <= self.radius * self.radius
+    }
+
+    /// Returns the point inside or on the surface of the cylinder that is
+    /// closest to `p`.
+    pub fn closest_point(&self, p: &Point3<S>) -> Point3<S> {
+        let len = self.axis.length();
+        let d = p.sub_p(&self.center);
+        if len == zero() {
+            let r2 = d.length2();
+            return if r2 > self.radius * self.radius {
+                self.center.add_v(&d.mul_s(self.radius / r2.sqrt()))
+            } else {
+                *p
+            };
+        }
+        let u = self.axis.div_s(len);
+        let h = d.dot(&u);
+        let radial = d.sub_v(&u.mul_s(h));
+        let r2 = radial.length2();
+        let radial = if r2 > self.radius * self.radius {
+            radial.mul_s(self.radius / r2.sqrt())
+        } else {
+            radial
+        };
+        self.center.add_v(&u.mul_s(h.partial_max(-len).partial_min(len))).add_v(&radial)
+    }
+}
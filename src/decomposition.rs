@@ -0,0 +1,369 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! LU and QR decompositions of square matrices, and linear system solvers
+//! built on them.
+
+use std::num::{zero, one, cast};
+
+use approx::epsilon;
+use array::FixedArray;
+use matrix::{Matrix, Matrix2, Matrix3, Matrix4};
+use num::BaseFloat;
+use vector::{Vector2, Vector3, Vector4};
+
+/// The LU decomposition of a square matrix `a`, with partial pivoting. This
+/// is a permutation matrix `p`, a lower triangular matrix `l` with ones on its
+/// diagonal, and an upper triangular matrix `u`, such that `p * a = l * u`.
+#[deriving(Clone, PartialEq)]
+pub struct Lu<M> {
+    /// `l` below the diagonal, and `u` on and above it
+    lu: M,
+    /// the row of `a` that each row of `p * a` is taken from
+    perm: [uint, ..4],
+    /// `true` if `p` swaps an odd number of rows
+    odd: bool,
+    singular: bool,
+}
+
+/// The QR decomposition of a square matrix `a`, computed with Householder
+/// reflections and column pivoting. This is an orthogonal matrix `q`, an
+/// upper triangular matrix `r` and a permutation matrix `p`, such that
+/// `a * p = q * r`. The columns are chosen so that the diagonal of `r`
+/// decreases in magnitude, which reveals the rank of the matrix.
+#[deriving(Clone, PartialEq)]
+pub struct Qr<M> {
+    q: M,
+    r: M,
+    /// the column of `a` that each column of `a * p` is taken from
+    perm: [uint, ..4],
+}
+
+macro_rules! decomposition(
+    ($Matrix:ident, $Vector:ident, $n:expr) => (
+        impl<S: BaseFloat + 'static> $Matrix<S> {
+            /// Computes the LU decomposition of the matrix, with partial
+            /// pivoting.
+            pub fn lu(&self) -> Lu<$Matrix<S>> {
+                let mut lu = *self;
+                let mut perm = [0u, 1, 2, 3];
+                let mut odd = false;
+                let mut singular = false;
+                {
+                    let a = lu.as_mut_fixed();
+                    let mut scale = zero::<S>();
+                    for c in range(0u, $n) {
+                        for r in range(0u, $n) { scale = scale.max(a[c][r].abs()); }
+                    }
+                    let tiny = scale * Float::epsilon() * cast($n).unwrap();
+
+                    for k in range(0u, $n) {
+                        // use the row with the largest entry in this column as
+                        // the pivot
+                        let mut p = k;
+                        for r in range(k + 1, $n) {
+                            if a[k][r].abs() > a[k][p].abs() { p = r; }
+                        }
+                        if p != k {
+                            for c in range(0u, $n) {
+                                let t = a[c][k];
+                                a[c][k] = a[c][p];
+                                a[c][p] = t;
+                            }
+                            let t = perm[k];
+                            perm[k] = perm[p];
+                            perm[p] = t;
+                            odd = !odd;
+                        }
+
+                        let pivot = a[k][k];
+                        if pivot.abs() <= tiny {
+                            singular = true;
+                            continue;
+                        }
+                        for r in range(k + 1, $n) {
+                            let f = a[k][r] / pivot;
+                            a[k][r] = f;
+                            for c in range(k + 1, $n) {
+                                a[c][r] = a[c][r] - f * a[c][k];
+                            }
+                        }
+                    }
+                }
+                Lu { lu: lu, perm: perm, odd: odd, singular: singular }
+            }
+
+            /// Computes the QR decomposition of the matrix, with column
+            /// pivoting.
+            pub fn qr(&self) -> Qr<$Matrix<S>> {
+                let mut q = $Matrix::identity();
+                let mut r = *self;
+                let mut perm = [0u, 1, 2, 3];
+                {
+                    let qa = q.as_mut_fixed();
+                    let ra = r.as_mut_fixed();
+                    for k in range(0u, $n) {
+                        // move the remaining column with the largest norm to
+                        // the front
+                        let mut p = k;
+                        let mut norm2 = -one::<S>();
+                        for c in range(k, $n) {
+                            let mut sum = zero::<S>();
+                            for i in range(k, $n) { sum = sum + ra[c][i] * ra[c][i]; }
+                            if sum > norm2 {
+                                norm2 = sum;
+                                p = c;
+                            }
+                        }
+                        if p != k {
+                            let t = ra[k];
+                            ra[k] = ra[p];
+                            ra[p] = t;
+                            let t = perm[k];
+                            perm[k] = perm[p];
+                            perm[p] = t;
+                        }
+                        if k + 1 == $n { break; }
+
+                        // reflect the column onto the diagonal, choosing the
+                        // sign that avoids cancellation
+                        let norm = norm2.sqrt();
+                        if norm == zero() { break; }
+                        let alpha = if ra[k][k] > zero() { -norm } else { norm };
+                        let mut v = [zero::<S>(), ..$n];
+                        for i in range(k, $n) { v[i] = ra[k][i]; }
+                        v[k] = v[k] - alpha;
+                        let mut v2 = zero::<S>();
+                        for i in range(k, $n) { v2 = v2 + v[i] * v[i]; }
+                        let two = one::<S>() + one::<S>();
+
+                        for c in range(k, $n) {
+                            let mut d = zero::<S>();
+                            for i in range(k, $n) { d = d + v[i] * ra[c][i]; }
+                            let f = two * d / v2;
+                            for i in range(k, $n) { ra[c][i] = ra[c][i] - f * v[i]; }
+                        }
+                        for i in range(k + 1, $n) { ra[k][i] = zero(); }
+                        for i in range(0u, $n) {
+                            let mut d = zero::<S>();
+                            for j in range(k, $n) { d = d + qa[j][i] * v[j]; }
+                            let f = two * d / v2;
+                            for j in range(k, $n) { qa[j][i] = qa[j][i] - f * v[j]; }
+                        }
+                    }
+                }
+                Qr { q: q, r: r, perm: perm }
+            }
+
+            /// Solves `self * x = b` for `x`, using the LU decomposition.
+            /// Returns `None` if the matrix is singular.
+            #[inline]
+            pub fn solve(&self, b: &$Vector<S>) -> Option<$Vector<S>> {
+                self.lu().solve(b)
+            }
+
+            /// Finds the `x` that minimizes the length of `self * x - b`,
+            /// using the QR decomposition. If the matrix is singular, there
+            /// are many such `x`, and one with a component of zero for each
+            /// redundant column is returned.
+            #[inline]
+            pub fn solve_least_squares(&self, b: &$Vector<S>) -> $Vector<S> {
+                self.qr().solve_least_squares(b)
+            }
+
+            /// Estimates the rank of the matrix from its QR decomposition,
+            /// treating diagonal elements of `r` that are smaller than
+            /// `epsilon` times the largest one as zero.
+            #[inline]
+            pub fn rank_eps(&self, epsilon: &S) -> uint {
+                self.qr().rank_eps(epsilon)
+            }
+
+            /// Estimates the rank of the matrix, using the default epsilon for
+            /// `S`.
+            #[inline]
+            pub fn rank(&self) -> uint {
+                self.qr().rank()
+            }
+
+            /// The condition number of the matrix in the 1-norm, which is
+            /// the factor by which relative errors in `b` can grow in the
+            /// solution of `self * x = b`. Returns infinity if the matrix is
+            /// singular.
+            pub fn condition_number(&self) -> S {
+                // the largest sum of the magnitudes of a column
+                let norm1 = |m: &$Matrix<S>| {
+                    let a = m.as_fixed();
+                    let mut max = zero::<S>();
+                    for c in range(0u, $n) {
+                        let mut sum = zero::<S>();
+                        for r in range(0u, $n) { sum = sum + a[c][r].abs(); }
+                        max = max.max(sum);
+                    }
+                    max
+                };
+                match self.lu().invert() {
+                    Some(inv) => norm1(self) * norm1(&inv),
+                    None => Float::infinity(),
+                }
+            }
+        }
+
+        impl<S: BaseFloat + 'static> Lu<$Matrix<S>> {
+            /// The lower triangular matrix `l`.
+            pub fn l(&self) -> $Matrix<S> {
+                let mut m = $Matrix::identity();
+                {
+                    let (a, lu) = (m.as_mut_fixed(), self.lu.as_fixed());
+                    for c in range(0u, $n) {
+                        for r in range(c + 1, $n) { a[c][r] = lu[c][r]; }
+                    }
+                }
+                m
+            }
+
+            /// The upper triangular matrix `u`.
+            pub fn u(&self) -> $Matrix<S> {
+                let mut m = $Matrix::zero();
+                {
+                    let (a, lu) = (m.as_mut_fixed(), self.lu.as_fixed());
+                    for c in range(0u, $n) {
+                        for r in range(0u, c + 1) { a[c][r] = lu[c][r]; }
+                    }
+                }
+                m
+            }
+
+            /// The permutation matrix `p`.
+            pub fn p(&self) -> $Matrix<S> {
+                let mut m = $Matrix::zero();
+                {
+                    let a = m.as_mut_fixed();
+                    for k in range(0u, $n) { a[self.perm[k]][k] = one(); }
+                }
+                m
+            }
+
+            /// Returns `true` if the matrix is singular, to within rounding
+            /// errors.
+            #[inline]
+            pub fn is_singular(&self) -> bool { self.singular }
+
+            /// The determinant of the matrix.
+            pub fn determinant(&self) -> S {
+                let lu = self.lu.as_fixed();
+                let mut det = if self.odd { -one::<S>() } else { one::<S>() };
+                for k in range(0u, $n) { det = det * lu[k][k]; }
+                det
+            }
+
+            /// Solves `a * x = b` for `x`. Returns `None` if the matrix is
+            /// singular.
+            pub fn solve(&self, b: &$Vector<S>) -> Option<$Vector<S>> {
+                if self.singular { return None; }
+                let lu = self.lu.as_fixed();
+                let mut x = *b;
+                {
+                    let (b, x) = (b.as_fixed(), x.as_mut_fixed());
+                    // solve l * y = p * b, then u * x = y
+                    for k in range(0u, $n) {
+                        let mut y = b[self.perm[k]];
+                        for j in range(0u, k) { y = y - lu[j][k] * x[j]; }
+                        x[k] = y;
+                    }
+                    for k in range(0u, $n).rev() {
+                        let mut y = x[k];
+                        for j in range(k + 1, $n) { y = y - lu[j][k] * x[j]; }
+                        x[k] = y / lu[k][k];
+                    }
+                }
+                Some(x)
+            }
+
+            /// The inverse of the matrix. Returns `None` if the matrix is
+            /// singular.
+            pub fn invert(&self) -> Option<$Matrix<S>> {
+                if self.singular { return None; }
+                let mut m = $Matrix::identity();
+                for c in range(0u, $n) {
+                    let column = self.solve(&m[c]).unwrap();
+                    m[c] = column;
+                }
+                Some(m)
+            }
+        }
+
+        impl<S: BaseFloat + 'static> Qr<$Matrix<S>> {
+            /// The orthogonal matrix `q`.
+            #[inline]
+            pub fn q(&self) -> $Matrix<S> { self.q }
+
+            /// The upper triangular matrix `r`.
+            #[inline]
+            pub fn r(&self) -> $Matrix<S> { self.r }
+
+            /// The permutation matrix `p`.
+            pub fn p(&self) -> $Matrix<S> {
+                let mut m = $Matrix::zero();
+                {
+                    let a = m.as_mut_fixed();
+                    for k in range(0u, $n) { a[k][self.perm[k]] = one(); }
+                }
+                m
+            }
+
+            /// Estimates the rank of the matrix, treating diagonal elements of
+            /// `r` that are smaller than `epsilon` times the largest one as
+            /// zero.
+            pub fn rank_eps(&self, epsilon: &S) -> uint {
+                let r = self.r.as_fixed();
+                let tolerance = r[0][0].abs() * *epsilon;
+                range(0u, $n).take_while(|&k| r[k][k].abs() > tolerance).count()
+            }
+
+            /// Estimates the rank of the matrix, using the default epsilon for
+            /// `S`.
+            #[inline]
+            pub fn rank(&self) -> uint {
+                self.rank_eps(&epsilon())
+            }
+
+            /// Finds the `x` that minimizes the length of `a * x - b`. If the
+            /// matrix is singular, there are many such `x`, and one with a
+            /// component of zero for each redundant column is returned.
+            pub fn solve_least_squares(&self, b: &$Vector<S>) -> $Vector<S> {
+                let rank = self.rank();
+                let c = self.q.transpose().mul_v(b);
+                let r = self.r.as_fixed();
+                let mut z = [zero::<S>(), ..$n];
+                for k in range(0u, rank).rev() {
+                    let mut y = c.as_fixed()[k];
+                    for j in range(k + 1, rank) { y = y - r[j][k] * z[j]; }
+                    z[k] = y / r[k][k];
+                }
+                let mut x = $Vector::zero();
+                {
+                    let x = x.as_mut_fixed();
+                    for k in range(0u, $n) { x[self.perm[k]] = z[k]; }
+                }
+                x
+            }
+        }
+    )
+)
+
+decomposition!(Matrix2, Vector2, 2u)
+decomposition!(Matrix3, Vector3, 3u)
+decomposition!(Matrix4, Vector4, 4u)
@@ -15,12 +15,17 @@
 
 //! View frustum for visibility determination
 
+use std::num::one;
+
+use aabb::{Aabb, Aabb3};
 use array::Array2;
-use matrix::Matrix4;
+use bound::{Bound, Relation, In, Cross, Out};
+use intersect::IntersectPoint;
+use matrix::{Matrix, Matrix4};
 use num::BaseFloat;
 use plane::Plane;
 use point::Point3;
-use vector::{Vector, EuclideanVector};
+use vector::{Vector, Vector4};
 
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Frustum<S> {
@@ -48,14 +53,54 @@ Frustum<S> {
         }
     }
 
-    /// Extracts frustum planes from a projection matrix
+    /// Extracts frustum planes from a projection matrix. The normals of the
+    /// planes point towards the inside of the frustum.
     pub fn from_matrix4(mat: Matrix4<S>) -> Frustum<S> {
-        Frustum::new(Plane::from_vector4(mat.row(3).add_v(&mat.row(0)).normalize()),
-                     Plane::from_vector4(mat.row(3).sub_v(&mat.row(0)).normalize()),
-                     Plane::from_vector4(mat.row(3).add_v(&mat.row(1)).normalize()),
-                     Plane::from_vector4(mat.row(3).sub_v(&mat.row(1)).normalize()),
-                     Plane::from_vector4(mat.row(3).add_v(&mat.row(2)).normalize()),
-                     Plane::from_vector4(mat.row(3).sub_v(&mat.row(2)).normalize()))
+        Frustum::new(Plane::from_vector4(mat.row(3).add_v(&mat.row(0))).normalize_normal(),
+                     Plane::from_vector4(mat.row(3).sub_v(&mat.row(0))).normalize_normal(),
+                     Plane::from_vector4(mat.row(3).add_v(&mat.row(1))).normalize_normal(),
+                     Plane::from_vector4(mat.row(3).sub_v(&mat.row(1))).normalize_normal(),
+                     Plane::from_vector4(mat.row(3).add_v(&mat.row(2))).normalize_normal(),
+                     Plane::from_vector4(mat.row(3).sub_v(&mat.row(2))).normalize_normal())
+    }
+
+    /// Computes the corners of the frustum by intersecting its planes. Returns
+    /// `None` if any three adjacent planes do not meet at a single point.
+    #[inline]
+    pub fn to_points(&self) -> Option<FrustumPoints<S>> {
+        FrustumPoints::from_frustum(self)
+    }
+
+    /// Find the spatial relation of a bound and the frustum.
+    #[inline]
+    pub fn contains<B: Bound<S>>(&self, bound: &B) -> Relation {
+        let (relation, _) = self.contains_masked(bound, 0b111111);
+        relation
+    }
+
+    /// Find the spatial relation of a bound and the frustum, only testing the
+    /// planes selected by `mask`. The planes are assigned bits in the order
+    /// left, right, bottom, top, near, far, starting from the least
+    /// significant bit.
+    ///
+    /// Along with the relation, this returns the mask of the planes that the
+    /// bound crosses. When the bounds form a hierarchy, this can be passed on
+    /// when testing the children of the bound, as they must lie inside the
+    /// remaining planes.
+    pub fn contains_masked<B: Bound<S>>(&self, bound: &B, mask: u8) -> (Relation, u8) {
+        let planes = [&self.left, &self.right, &self.bottom,
+                      &self.top,  &self.near,  &self.far];
+        let mut crossed = 0u8;
+        for (i, plane) in planes.iter().enumerate() {
+            let bit = 1u8 << i;
+            if mask & bit == 0 { continue; }
+            match bound.relate_plane(*plane) {
+                Out   => return (Out, 0),
+                Cross => crossed = crossed | bit,
+                In    => (),
+            }
+        }
+        (if crossed == 0 { In } else { Cross }, crossed)
     }
 }
 
@@ -70,3 +115,65 @@ pub struct FrustumPoints<S> {
     pub far_bottom_left:   Point3<S>,
     pub far_bottom_right:  Point3<S>,
 }
+
+impl<S: BaseFloat + 'static>
+FrustumPoints<S> {
+    /// Computes the corners of a frustum by intersecting each combination of
+    /// three adjacent planes. Returns `None` if any of the corners could not be
+    /// found, for example if two of the planes are parallel.
+    pub fn from_frustum(frustum: &Frustum<S>) -> Option<FrustumPoints<S>> {
+        let corner = |a: &Plane<S>, b: &Plane<S>, c: &Plane<S>| {
+            (a.clone(), b.clone(), c.clone()).intersection_point()
+        };
+        let f = frustum;
+        match (corner(&f.near, &f.top,    &f.left), corner(&f.near, &f.top,    &f.right),
+               corner(&f.near, &f.bottom, &f.left), corner(&f.near, &f.bottom, &f.right),
+               corner(&f.far,  &f.top,    &f.left), corner(&f.far,  &f.top,    &f.right),
+               corner(&f.far,  &f.bottom, &f.left), corner(&f.far,  &f.bottom, &f.right)) {
+            (Some(ntl), Some(ntr), Some(nbl), Some(nbr),
+             Some(ftl), Some(ftr), Some(fbl), Some(fbr)) => Some(FrustumPoints {
+                near_top_left:     ntl,
+                near_top_right:    ntr,
+                near_bottom_left:  nbl,
+                near_bottom_right: nbr,
+                far_top_left:      ftl,
+                far_top_right:     ftr,
+                far_bottom_left:   fbl,
+                far_bottom_right:  fbr,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Computes the corners of a frustum by transforming the corners of the
+    /// normalized device coordinate cube by an inverse projection, or inverse
+    /// view-projection, matrix.
+    pub fn from_inverse_matrix4(inv: Matrix4<S>) -> FrustumPoints<S> {
+        let one = one::<S>();
+        let corner = |x: S, y: S, z: S| {
+            Point3::from_homogeneous(&inv.mul_v(&Vector4::new(x, y, z, one)))
+        };
+        FrustumPoints {
+            near_top_left:     corner(-one,  one, -one),
+            near_top_right:    corner( one,  one, -one),
+            near_bottom_left:  corner(-one, -one, -one),
+            near_bottom_right: corner( one, -one, -one),
+            far_top_left:      corner(-one,  one,  one),
+            far_top_right:     corner( one,  one,  one),
+            far_bottom_left:   corner(-one, -one,  one),
+            far_bottom_right:  corner( one, -one,  one),
+        }
+    }
+
+    /// Computes the smallest axis-aligned bounding box that contains all of
+    /// the corners of the frustum.
+    pub fn to_aabb3(&self) -> Aabb3<S> {
+        Aabb3::new(self.near_top_left, self.near_top_right)
+            .grow(&self.near_bottom_left)
+            .grow(&self.near_bottom_right)
+            .grow(&self.far_top_left)
+            .grow(&self.far_top_right)
+            .grow(&self.far_bottom_left)
+            .grow(&self.far_bottom_right)
+    }
+}
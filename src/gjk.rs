@@ -0,0 +1,411 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Distance and penetration queries between convex shapes, using the
+//! Gilbert–Johnson–Keerthi (GJK) and expanding polytope (EPA) algorithms.
+//!
+//! Both algorithms work on the Minkowski difference `a - b` of two shapes,
+//! which contains the origin exactly when the shapes overlap. Its support
+//! points are found from the support points of the shapes themselves, so any
+//! pair of shapes implementing `SupportFunction` can be queried.
+
+use std::num::{zero, one};
+
+use approx::epsilon;
+use num::BaseFloat;
+use point::{Point, Point3};
+use support::SupportFunction;
+use vector::{Vector, Vector3, EuclideanVector};
+
+/// The maximum number of iterations, after which the current estimate is
+/// returned. This guards against cycling due to rounding errors.
+static MAX_ITERATIONS: uint = 128;
+
+/// The depth and direction of the overlap between two convex shapes.
+///
+/// # Fields
+///
+/// - `normal`: a unit vector pointing from the first shape towards the second
+/// - `depth`: the distance that the second shape must be moved along `normal`
+///   for the shapes to be just touching
+#[deriving(Clone, PartialEq, Encodable, Decodable)]
+pub struct Penetration<S> {
+    pub normal: Vector3<S>,
+    pub depth: S,
+}
+
+/// Finds the distance between two convex shapes, and the closest points on
+/// each of them, in that order. Returns `None` if the shapes overlap, or if
+/// either of them is empty.
+pub fn gjk_distance<S: BaseFloat, A: SupportFunction<S>, B: SupportFunction<S>>(a: &A, b: &B) -> Option<(S, Point3<S>, Point3<S>)> {
+    if !a.has_support() || !b.has_support() { return None; }
+    match gjk(a, b) {
+        Overlapping(_) => None,
+        Separated(simplex, weights) => {
+            // the closest points are the same combinations of the support
+            // points of each shape as the closest point of the simplex
+            let mut pa = Vector3::zero();
+            let mut pb = Vector3::zero();
+            for (v, &w) in simplex.iter().zip(weights.iter()) {
+                pa.add_self_v(&v.a.to_vec().mul_s(w));
+                pb.add_self_v(&v.b.to_vec().mul_s(w));
+            }
+            Some((pa.sub_v(&pb).length(), Point::from_vec(&pa), Point::from_vec(&pb)))
+        }
+    }
+}
+
+/// Tests whether two convex shapes overlap. An empty shape overlaps nothing.
+pub fn gjk_intersects<S: BaseFloat, A: SupportFunction<S>, B: SupportFunction<S>>(a: &A, b: &B) -> bool {
+    if !a.has_support() || !b.has_support() { return false; }
+    match gjk(a, b) {
+        Overlapping(_) => true,
+        Separated(..) => false,
+    }
+}
+
+/// Finds the smallest translation of `b` that separates two overlapping
+/// convex shapes. Returns `None` if the shapes do not overlap, if either of
+/// them is empty, or if their Minkowski difference is flat, as it is for two
+/// coplanar triangles.
+///
+/// The simplex that GJK found to contain the origin is expanded into a
+/// polytope that approximates the Minkowski difference more closely near the
+/// origin, until the face closest to the origin lies on its boundary. Curved
+/// shapes are approximated to within the default epsilon of `S`.
+pub fn epa_penetration<S: BaseFloat, A: SupportFunction<S>, B: SupportFunction<S>>(a: &A, b: &B) -> Option<Penetration<S>> {
+    if !a.has_support() || !b.has_support() { return None; }
+    let mut vertices = match gjk(a, b) {
+        Overlapping(simplex) => simplex,
+        Separated(..) => return None,
+    };
+    if !expand_simplex(a, b, &mut vertices) { return None; }
+
+    // orient the faces of the tetrahedron so that their normals point away
+    // from the opposite vertex
+    let mut faces = Vec::new();
+    for &(i, j, k, l) in [(0u, 1u, 2u, 3u), (0, 3, 1, 2), (0, 2, 3, 1), (1, 3, 2, 0)].iter() {
+        let f = face(vertices.as_slice(), i, j, k);
+        if f.normal.dot(&vertices[l].p.sub_v(&vertices[i].p)) > zero() {
+            faces.push(face(vertices.as_slice(), i, k, j));
+        } else {
+            faces.push(f);
+        }
+    }
+
+    for _ in range(0, MAX_ITERATIONS) {
+        let Face { normal, dist, .. } = faces[closest_face(faces.as_slice())];
+        let w = support(a, b, &normal);
+
+        // stop once the closest face lies on the boundary of the difference
+        let tolerance = epsilon::<S>() * if dist > one() { dist } else { one() };
+        if normal.dot(&w.p) - dist <= tolerance {
+            return Some(Penetration { normal: normal, depth: dist });
+        }
+
+        // remove the faces that can be seen from the new vertex, and close the
+        // hole with faces joining its boundary to the new vertex
+        let index = vertices.len();
+        vertices.push(w);
+        let mut horizon: Vec<(uint, uint)> = Vec::new();
+        let mut kept = Vec::new();
+        for f in faces.iter() {
+            if f.normal.dot(&w.p.sub_v(&vertices[f.v[0]].p)) > zero() {
+                for &(i, j) in [(f.v[0], f.v[1]), (f.v[1], f.v[2]), (f.v[2], f.v[0])].iter() {
+                    // an edge shared by two removed faces is inside the hole
+                    match horizon.iter().position(|&e| e == (j, i)) {
+                        Some(n) => { horizon.swap_remove(n); }
+                        None => horizon.push((i, j)),
+                    }
+                }
+            } else {
+                kept.push(*f);
+            }
+        }
+        for &(i, j) in horizon.iter() {
+            kept.push(face(vertices.as_slice(), i, j, index));
+        }
+        faces = kept;
+    }
+
+    let Face { normal, dist, .. } = faces[closest_face(faces.as_slice())];
+    Some(Penetration { normal: normal, depth: dist })
+}
+
+/// A vertex of the Minkowski difference, along with the support points of
+/// the two shapes that it was found from.
+struct Vertex<S> {
+    p: Vector3<S>,
+    a: Point3<S>,
+    b: Point3<S>,
+}
+
+/// Returns the vertex of the Minkowski difference `a - b` that is furthest
+/// along `direction`.
+fn support<S: BaseFloat, A: SupportFunction<S>, B: SupportFunction<S>>(a: &A, b: &B, direction: &Vector3<S>) -> Vertex<S> {
+    let pa = a.support(direction);
+    let pb = b.support(&-*direction);
+    Vertex { p: pa.sub_p(&pb), a: pa, b: pb }
+}
+
+enum GjkResult<S> {
+    /// The shapes are separated. The closest point of the difference to the
+    /// origin is a weighted sum of the vertices of the simplex.
+    Separated(Vec<Vertex<S>>, Vec<S>),
+    /// The shapes overlap, and the simplex contains the origin.
+    Overlapping(Vec<Vertex<S>>),
+}
+
+/// Searches for the point of the Minkowski difference that is closest to the
+/// origin, by refining a simplex of up to four of its vertices (see _A Fast
+/// Procedure for Computing the Distance Between Complex Objects in
+/// Three-Dimensional Space_, Gilbert, Johnson and Keerthi, 1988).
+fn gjk<S: BaseFloat, A: SupportFunction<S>, B: SupportFunction<S>>(a: &A, b: &B) -> GjkResult<S> {
+    let mut simplex = Vec::new();
+    let mut weights = Vec::new();
+    simplex.push(support(a, b, &Vector3::unit_x()));
+    weights.push(one::<S>());
+
+    for _ in range(0, MAX_ITERATIONS) {
+        let mut v = Vector3::zero();
+        for (vertex, &w) in simplex.iter().zip(weights.iter()) {
+            v.add_self_v(&vertex.p.mul_s(w));
+        }
+        let v2 = v.length2();
+        if v2 <= epsilon::<S>() * epsilon::<S>() { return Overlapping(simplex); }
+
+        // stop when the new vertex brings the simplex no closer to the origin
+        let w = support(a, b, &-v);
+        if v2 - v.dot(&w.p) <= epsilon::<S>() * v2 { return Separated(simplex, weights); }
+        simplex.push(w);
+
+        let points: Vec<Vector3<S>> = simplex.iter().map(|vertex| vertex.p).collect();
+        match closest_to_origin(points.as_slice()) {
+            None => return Overlapping(simplex),
+            Some(feature) => {
+                // keep only the vertices spanning the closest feature
+                let reduced: Vec<Vertex<S>> = feature.iter().map(|&(i, _)| simplex[i]).collect();
+                simplex = reduced;
+                weights = feature.iter().map(|&(_, w)| w).collect();
+            }
+        }
+    }
+    Separated(simplex, weights)
+}
+
+/// Finds the point of a simplex that is closest to the origin, as the indices
+/// of the vertices of the smallest feature containing it, paired with their
+/// barycentric weights. Returns `None` if the simplex is a tetrahedron that
+/// contains the origin.
+fn closest_to_origin<S: BaseFloat>(s: &[Vector3<S>]) -> Option<Vec<(uint, S)>> {
+    match s.len() {
+        1 => {
+            let mut feature = Vec::new();
+            feature.push((0u, one::<S>()));
+            Some(feature)
+        }
+        2 => Some(closest_on_segment(s, 0, 1)),
+        3 => Some(closest_on_triangle(s, 0, 1, 2)),
+        _ => closest_on_tetrahedron(s),
+    }
+}
+
+/// Evaluates a weighted sum of the vertices of a simplex.
+fn combine<S: BaseFloat>(s: &[Vector3<S>], feature: &[(uint, S)]) -> Vector3<S> {
+    feature.iter().fold(Vector3::zero(), |sum, &(i, w)| sum.add_v(&s[i].mul_s(w)))
+}
+
+fn closest_on_segment<S: BaseFloat>(s: &[Vector3<S>], i: uint, j: uint) -> Vec<(uint, S)> {
+    let ab = s[j].sub_v(&s[i]);
+    let t = -s[i].dot(&ab);
+    let len2 = ab.length2();
+    let mut feature = Vec::new();
+    if t <= zero() {
+        feature.push((i, one::<S>()));
+    } else if t >= len2 {
+        feature.push((j, one::<S>()));
+    } else {
+        let t = t / len2;
+        feature.push((i, one::<S>() - t));
+        feature.push((j, t));
+    }
+    feature
+}
+
+/// Finds the closest point to the origin by testing which of the Voronoi
+/// regions of the vertices, edges and face of the triangle contains it (see
+/// _Real Time Collision Detection_, p. 141).
+fn closest_on_triangle<S: BaseFloat>(s: &[Vector3<S>], i: uint, j: uint, k: uint) -> Vec<(uint, S)> {
+    let (a, b, c) = (s[i], s[j], s[k]);
+    let ab = b.sub_v(&a);
+    let ac = c.sub_v(&a);
+    let mut feature = Vec::new();
+
+    let d1 = -ab.dot(&a);
+    let d2 = -ac.dot(&a);
+    if d1 <= zero() && d2 <= zero() {
+        feature.push((i, one::<S>()));
+        return feature;
+    }
+
+    let d3 = -ab.dot(&b);
+    let d4 = -ac.dot(&b);
+    if d3 >= zero() && d4 <= d3 {
+        feature.push((j, one::<S>()));
+        return feature;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero() && d1 >= zero() && d3 <= zero() {
+        let v = d1 / (d1 - d3);
+        feature.push((i, one::<S>() - v));
+        feature.push((j, v));
+        return feature;
+    }
+
+    let d5 = -ab.dot(&c);
+    let d6 = -ac.dot(&c);
+    if d6 >= zero() && d5 <= d6 {
+        feature.push((k, one::<S>()));
+        return feature;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero() && d2 >= zero() && d6 <= zero() {
+        let w = d2 / (d2 - d6);
+        feature.push((i, one::<S>() - w));
+        feature.push((k, w));
+        return feature;
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero() && d4 - d3 >= zero() && d5 - d6 >= zero() {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        feature.push((j, one::<S>() - w));
+        feature.push((k, w));
+        return feature;
+    }
+
+    let denom = va + vb + vc;
+    if denom <= zero() {
+        // the triangle is degenerate, so the closest point is on an edge
+        let mut edges = Vec::new();
+        edges.push(closest_on_segment(s, i, j));
+        edges.push(closest_on_segment(s, j, k));
+        edges.push(closest_on_segment(s, i, k));
+        return nearest(s, edges.move_iter());
+    }
+    let v = vb / denom;
+    let w = vc / denom;
+    feature.push((i, one::<S>() - v - w));
+    feature.push((j, v));
+    feature.push((k, w));
+    feature
+}
+
+/// Finds the closest point to the origin on the faces of the tetrahedron that
+/// the origin lies outside of (see _Real Time Collision Detection_, p. 142).
+fn closest_on_tetrahedron<S: BaseFloat>(s: &[Vector3<S>]) -> Option<Vec<(uint, S)>> {
+    let mut candidates = Vec::new();
+    for &(i, j, k, l) in [(0u, 1u, 2u, 3u), (0, 3, 1, 2), (0, 2, 3, 1), (1, 3, 2, 0)].iter() {
+        let n = s[j].sub_v(&s[i]).cross(&s[k].sub_v(&s[i]));
+        let side_origin = -n.dot(&s[i]);
+        let side_opposite = n.dot(&s[l].sub_v(&s[i]));
+        // every face of a flat tetrahedron needs to be tested
+        if side_origin * side_opposite < zero() || side_opposite == zero() {
+            candidates.push(closest_on_triangle(s, i, j, k));
+        }
+    }
+    if candidates.is_empty() { None } else { Some(nearest(s, candidates.move_iter())) }
+}
+
+/// Returns the feature that is closest to the origin.
+fn nearest<S: BaseFloat, I: Iterator<Vec<(uint, S)>>>(s: &[Vector3<S>], mut features: I) -> Vec<(uint, S)> {
+    let first = features.next().unwrap();
+    let first_dist = combine(s, first.as_slice()).length2();
+    features.fold((first, first_dist), |(best, best_dist), feature| {
+        let dist = combine(s, feature.as_slice()).length2();
+        if dist < best_dist { (feature, dist) } else { (best, best_dist) }
+    }).val0()
+}
+
+/// A face of the polytope built by EPA, with its outward unit normal and its
+/// distance from the origin.
+struct Face<S> {
+    v: [uint, ..3],
+    normal: Vector3<S>,
+    dist: S,
+}
+
+fn face<S: BaseFloat>(vertices: &[Vertex<S>], i: uint, j: uint, k: uint) -> Face<S> {
+    let a = vertices[i].p;
+    let n = vertices[j].p.sub_v(&a).cross(&vertices[k].p.sub_v(&a));
+    let len = n.length();
+    if len == zero() {
+        // a face with no area can't be the closest one
+        Face { v: [i, j, k], normal: n, dist: Float::infinity() }
+    } else {
+        let normal = n.div_s(len);
+        Face { v: [i, j, k], normal: normal, dist: normal.dot(&a) }
+    }
+}
+
+fn closest_face<S: BaseFloat>(faces: &[Face<S>]) -> uint {
+    let mut best = 0u;
+    for i in range(1, faces.len()) {
+        if faces[i].dist < faces[best].dist { best = i; }
+    }
+    best
+}
+
+/// Adds vertices to a simplex containing the origin until it is a
+/// tetrahedron. Returns `false` if the Minkowski difference is flat.
+fn expand_simplex<S: BaseFloat, A: SupportFunction<S>, B: SupportFunction<S>>(a: &A, b: &B, vertices: &mut Vec<Vertex<S>>) -> bool {
+    let axes = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()];
+    while vertices.len() < 4 {
+        // find directions leading away from the affine hull of the simplex
+        let p0 = vertices[0].p;
+        let mut directions = Vec::new();
+        match vertices.len() {
+            1 => {
+                for axis in axes.iter() { directions.push(*axis); }
+            }
+            2 => {
+                let edge = vertices[1].p.sub_v(&p0);
+                for axis in axes.iter() { directions.push(edge.cross(axis)); }
+            }
+            _ => {
+                let n = vertices[1].p.sub_v(&p0).cross(&vertices[2].p.sub_v(&p0));
+                directions.push(n);
+            }
+        }
+
+        let mut added = false;
+        for d in directions.iter() {
+            if d.length2() == zero() { continue; }
+            for dir in [*d, -*d].iter() {
+                let w = support(a, b, dir);
+                if dir.dot(&w.p.sub_v(&p0)) > epsilon::<S>() * dir.length() {
+                    vertices.push(w);
+                    added = true;
+                    break;
+                }
+            }
+            if added { break; }
+        }
+        if !added { return false; }
+    }
+    true
+}
@@ -15,14 +15,20 @@
 
 use std::num::{Zero, zero, One, one};
 
-use aabb::{Aabb2};
-use line::{Line2};
+use aabb::{Aabb, Aabb2, Aabb3};
+use approx::epsilon;
+use bound::Cross;
+use capsule::Capsule;
+use cylinder::Cylinder;
+use line::{Line, Line2, LineIntersection, Crossing, Overlap};
 use num::{BaseFloat};
+use obb::{Obb2, Obb3};
 use plane::Plane;
 use point::{Point, Point2, Point3};
-use ray::{Ray2, Ray3};
+use ray::{Ray, Ray2, Ray3};
 use sphere::Sphere;
-use vector::{Vector, Vector2};
+use triangle::Triangle3;
+use vector::{Vector, Vector2, EuclideanVector};
 
 pub trait Intersect<Result> {
 	fn intersection(&self) -> Result;
@@ -45,6 +51,9 @@ impl<S: BaseFloat> Intersect<Option<S>> for (Ray2<S>, Aabb2<S>) {
 			tmin = tmin.max(tx1.min(tx2));
 			tmax = tmax.min(tx1.max(tx2));
 		}
+		else if ray.origin.x < aabb.min.x || ray.origin.x > aabb.max.x {
+			return None;
+		}
 
 		if ray.direction.y != zero() {
 			let ty1 = (aabb.min.y - ray.origin.y) / ray.direction.y;
@@ -52,6 +61,9 @@ impl<S: BaseFloat> Intersect<Option<S>> for (Ray2<S>, Aabb2<S>) {
 			tmin = tmin.max(ty1.min(ty2));
 			tmax = tmax.min(ty1.max(ty2));
 		}
+		else if ray.origin.y < aabb.min.y || ray.origin.y > aabb.max.y {
+			return None;
+		}
 
 		if tmin < zero() && tmax < zero() {
 			None
@@ -81,6 +93,127 @@ impl<S: BaseFloat> IntersectPoint<Option<Point2<S>>> for (Ray2<S>, Aabb2<S>) {
 	}
 }
 
+/// Determines where two segments meet. If the segments are collinear and
+/// overlap, the overlapping part is returned, in the direction of the first
+/// segment.
+impl<S: BaseFloat> Intersect<Option<LineIntersection<Point2<S>>>> for (Line2<S>, Line2<S>) {
+	fn intersection(&self) -> Option<LineIntersection<Point2<S>>> {
+		let (ref a, ref b) = *self;
+
+		let p = a.origin;
+		let q = b.origin;
+		let r = a.dest.sub_p(&a.origin);
+		let s = b.dest.sub_p(&b.origin);
+		let qmp = q.sub_p(&p);
+
+		let r_cross_s = r.perp_dot(&s);
+		let qmp_cross_r = qmp.perp_dot(&r);
+		let rr = r.dot(&r);
+		let ss = s.dot(&s);
+
+		if r_cross_s == zero() {
+			if rr == zero() {
+				// the first segment is a point, so it must lie on the second
+				let on_b = if ss == zero() {
+					p == q
+				} else {
+					let u = -qmp.dot(&s) / ss;
+					qmp.perp_dot(&s) == zero() && u >= zero() && u <= one()
+				};
+				return if on_b { Some(Crossing(p)) } else { None };
+			}
+
+			if qmp_cross_r != zero() {
+				// parallel
+				return None;
+			}
+
+			// collinear, so find the overlap in terms of the first segment
+			let t0 = qmp.dot(&r) / rr;
+			let t1 = t0 + s.dot(&r) / rr;
+			let tmin = t0.min(t1).max(zero());
+			let tmax = t0.max(t1).min(one());
+
+			return if tmin > tmax {
+				None
+			} else if tmin == tmax {
+				Some(Crossing(p.add_v(&r.mul_s(tmin))))
+			} else {
+				Some(Overlap(Line::new(p.add_v(&r.mul_s(tmin)), p.add_v(&r.mul_s(tmax)))))
+			};
+		}
+
+		let t = qmp.perp_dot(&s) / r_cross_s;
+		let u = qmp_cross_r / r_cross_s;
+
+		if t >= zero() && t <= one() && u >= zero() && u <= one() {
+			Some(Crossing(p.add_v(&r.mul_s(t))))
+		} else {
+			None
+		}
+	}
+}
+
+/// Clips a segment against a box, using the Liang–Barsky algorithm. Returns
+/// the part of the segment that lies inside the box, if any.
+impl<S: BaseFloat> Intersect<Option<Line2<S>>> for (Line2<S>, Aabb2<S>) {
+	fn intersection(&self) -> Option<Line2<S>> {
+		let (ref line, ref aabb) = *self;
+		let d = line.dest.sub_p(&line.origin);
+		let mut t0: S = zero();
+		let mut t1: S = one();
+
+		for i in range(0u, 2) {
+			if !clip_param(-d[i], line.origin[i] - aabb.min[i], &mut t0, &mut t1) ||
+			   !clip_param(d[i], aabb.max[i] - line.origin[i], &mut t0, &mut t1) {
+				return None;
+			}
+		}
+
+		Some(Line::new(line.origin.add_v(&d.mul_s(t0)), line.origin.add_v(&d.mul_s(t1))))
+	}
+}
+
+/// Clips the parameter range `[t0, t1]` against a single boundary of a
+/// Liang–Barsky clip region, returning `false` if nothing remains.
+fn clip_param<S: BaseFloat>(p: S, q: S, t0: &mut S, t1: &mut S) -> bool {
+	if p == zero() {
+		// parallel to the boundary, so it must be on the inside
+		q >= zero()
+	} else {
+		let r = q / p;
+		if p < zero() {
+			if r > *t1 { return false; }
+			if r > *t0 { *t0 = r; }
+		} else {
+			if r < *t0 { return false; }
+			if r < *t1 { *t1 = r; }
+		}
+		true
+	}
+}
+
+/// Determines the parameter at which a ray first hits the surface of a box,
+/// using the slab method. If the origin of the ray is inside the box, this is
+/// the parameter at which the ray leaves it. Use `Aabb3::intersect_ray` to
+/// also find the normal of the face that was hit.
+impl<S: BaseFloat> Intersect<Option<S>> for (Ray3<S>, Aabb3<S>) {
+	fn intersection(&self) -> Option<S> {
+		let (ref ray, ref aabb) = *self;
+		aabb.intersect_ray(ray).map(|(t, _)| t)
+	}
+}
+
+impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Ray3<S>, Aabb3<S>) {
+	fn intersection_point(&self) -> Option<Point3<S>> {
+		let (ref ray, _) = *self;
+		match self.intersection() {
+			Some(t) => Some(ray.origin.add_v(&ray.direction.mul_s(t))),
+			_ => None,
+		}
+	}
+}
+
 /// Determines if an intersection between a ray and a line segments is found.
 impl<S: BaseFloat> IntersectPoint<Option<Point2<S>>> for (Ray2<S>, Line2<S>) {
     fn intersection_point(&self) -> Option<Point2<S>> {
@@ -152,27 +285,285 @@ impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Plane<S>, Ray3<S>) {
 	}
 }
 
+/// Determines the line of intersection between two planes, returned as a ray
+/// lying in both planes with a normalized direction. Returns `None` if the
+/// planes are parallel or coincident.
 impl<S: BaseFloat> Intersect<Option<Ray3<S>>> for (Plane<S>, Plane<S>) {
-    fn intersection(&self) -> Option<Ray3<S>> {
-        fail!("Not yet implemented");
-    }
+	fn intersection(&self) -> Option<Ray3<S>> {
+		let (ref p1, ref p2) = *self;
+
+		// the direction of the line is perpendicular to both normals
+		let u = p1.n.cross(&p2.n);
+		let denom = u.dot(&u);
+
+		// scale the tolerance so that it does not depend on the lengths of
+		// the normals: |n1 x n2|^2 = |n1|^2 |n2|^2 sin^2(theta)
+		let scale = p1.n.length2() * p2.n.length2();
+		if denom <= scale * epsilon() { return None; }
+
+		// see _Real Time Collision Detection_, p. 210
+		let w = p1.n.mul_s(p2.d).sub_v(&p2.n.mul_s(p1.d));
+		let p = w.cross(&u).div_s(denom);
+		Some(Ray::new(Point::from_vec(&p), u.normalize()))
+	}
 }
 
+/// Determines the point at which three planes meet. Returns `None` if any two
+/// of the planes are parallel, or if all three share a common line.
 impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Plane<S>, Plane<S>, Plane<S>) {
-    fn intersection_point(&self) -> Option<Point3<S>> {
-        fail!("Not yet implemented");
-    }
+	fn intersection_point(&self) -> Option<Point3<S>> {
+		let (ref p1, ref p2, ref p3) = *self;
+
+		let u = p2.n.cross(&p3.n);
+		let denom = p1.n.dot(&u);
+
+		// as above, compare the triple product against the product of the
+		// lengths of the normals rather than an absolute tolerance
+		let scale = p1.n.length2() * p2.n.length2() * p3.n.length2();
+		if denom * denom <= scale * epsilon() { return None; }
+
+		// see _Real Time Collision Detection_, p. 212
+		let p = u.mul_s(p1.d)
+		         .add_v(&p3.n.cross(&p1.n).mul_s(p2.d))
+		         .add_v(&p1.n.cross(&p2.n).mul_s(p3.d))
+		         .div_s(-denom);
+		Some(Point::from_vec(&p))
+	}
 }
 
-impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Sphere<S>, Ray3<S>) {
-	fn intersection_point(&self) -> Option<Point3<S>> {
+/// Determines the parameters at which a ray enters and exits a sphere, in that
+/// order. If the origin of the ray is inside the sphere, the entry parameter
+/// is negative. Returns `None` if the ray misses the sphere, if the sphere
+/// lies entirely behind the ray, or if the direction of the ray is zero.
+impl<S: BaseFloat> Intersect<Option<(S, S)>> for (Sphere<S>, Ray3<S>) {
+	fn intersection(&self) -> Option<(S, S)> {
 		let (ref s, ref r) = *self;
 		let l = s.center.sub_p(&r.origin);
-		let tca = l.dot(&r.direction);
-		if tca < zero() { return None; }
-		let d2 = l.dot(&l) - tca*tca;
-		if d2 > s.radius*s.radius { return None; }
-		let thc = (s.radius*s.radius - d2).sqrt();
-		Some(r.origin.add_v(&r.direction.mul_s(tca - thc)))
+		let a = r.direction.dot(&r.direction);
+		if a == zero() { return None; }
+		let b = l.dot(&r.direction);
+		let c = l.dot(&l) - s.radius * s.radius;
+		let discr = b * b - a * c;
+		if discr < zero() { return None; }
+		let sqrt_discr = discr.sqrt();
+		let t1 = (b + sqrt_discr) / a;
+		if t1 < zero() { return None; }
+		Some(((b - sqrt_discr) / a, t1))
+	}
+}
+
+/// Determines the first point at which a ray hits the surface of a sphere. If
+/// the origin of the ray is inside the sphere, this is the point at which the
+/// ray leaves it.
+impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Sphere<S>, Ray3<S>) {
+	fn intersection_point(&self) -> Option<Point3<S>> {
+		let (_, ref r) = *self;
+		match self.intersection() {
+			Some((t0, t1)) => {
+				let t = if t0 < zero() { t1 } else { t0 };
+				Some(r.origin.add_v(&r.direction.mul_s(t)))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl<S: BaseFloat> Intersect<bool> for (Sphere<S>, Sphere<S>) {
+	fn intersection(&self) -> bool {
+		let (ref a, ref b) = *self;
+		let r = a.radius + b.radius;
+		a.center.sub_p(&b.center).length2() <= r * r
+	}
+}
+
+impl<S: BaseFloat> Intersect<bool> for (Sphere<S>, Aabb3<S>) {
+	fn intersection(&self) -> bool {
+		let (ref s, ref aabb) = *self;
+		aabb.distance2(&s.center) <= s.radius * s.radius
+	}
+}
+
+impl<S: BaseFloat> Intersect<bool> for (Sphere<S>, Plane<S>) {
+	fn intersection(&self) -> bool {
+		let (ref s, ref p) = *self;
+		p.relate(s) == Cross
+	}
+}
+
+impl<S: BaseFloat + 'static> Intersect<bool> for (Obb2<S>, Obb2<S>) {
+	fn intersection(&self) -> bool {
+		let (ref a, ref b) = *self;
+		a.intersects(b)
+	}
+}
+
+impl<S: BaseFloat + 'static> Intersect<bool> for (Obb2<S>, Aabb2<S>) {
+	fn intersection(&self) -> bool {
+		let (ref a, ref b) = *self;
+		a.intersects(&Obb2::from_aabb2(b))
+	}
+}
+
+impl<S: BaseFloat + 'static> Intersect<bool> for (Obb3<S>, Obb3<S>) {
+	fn intersection(&self) -> bool {
+		let (ref a, ref b) = *self;
+		a.intersects(b)
+	}
+}
+
+impl<S: BaseFloat + 'static> Intersect<bool> for (Obb3<S>, Aabb3<S>) {
+	fn intersection(&self) -> bool {
+		let (ref a, ref b) = *self;
+		a.intersects(&Obb3::from_aabb3(b))
+	}
+}
+
+/// Determines the parameters at which a ray enters and exits a cylinder, in
+/// that order. If the origin of the ray is inside the cylinder, the entry
+/// parameter is negative. Returns `None` if the ray misses the cylinder, or
+/// if the cylinder lies entirely behind the ray.
+impl<S: BaseFloat> Intersect<Option<(S, S)>> for (Cylinder<S>, Ray3<S>) {
+	fn intersection(&self) -> Option<(S, S)> {
+		let (ref cyl, ref r) = *self;
+		let len = cyl.axis.length();
+		if len == zero() {
+			return (Sphere::new(cyl.center, cyl.radius), *r).intersection();
+		}
+		let u = cyl.axis.div_s(len);
+		let o = r.origin.sub_p(&cyl.center);
+		let (oa, da) = (o.dot(&u), r.direction.dot(&u));
+
+		let mut tmin: S = Float::neg_infinity();
+		let mut tmax: S = Float::infinity();
+
+		// the slab between the two caps
+		if da != zero() {
+			let t1 = (-len - oa) / da;
+			let t2 = (len - oa) / da;
+			tmin = t1.min(t2);
+			tmax = t1.max(t2);
+		}
+		else if oa < -len || oa > len {
+			return None;
+		}
+
+		// the infinite cylinder around the axis
+		let op = o.sub_v(&u.mul_s(oa));
+		let dp = r.direction.sub_v(&u.mul_s(da));
+		let a = dp.dot(&dp);
+		let b = op.dot(&dp);
+		let c = op.dot(&op) - cyl.radius * cyl.radius;
+		if a != zero() {
+			let discr = b * b - a * c;
+			if discr < zero() { return None; }
+			let sqrt_discr = discr.sqrt();
+			tmin = tmin.max((-b - sqrt_discr) / a);
+			tmax = tmax.min((-b + sqrt_discr) / a);
+		}
+		else if c > zero() {
+			return None;
+		}
+
+		if tmax < tmin || tmax < zero() { None }
+		else { Some((tmin, tmax)) }
+	}
+}
+
+/// Determines the first point at which a ray hits the surface of a cylinder.
+/// If the origin of the ray is inside the cylinder, this is the point at which
+/// the ray leaves it.
+impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Cylinder<S>, Ray3<S>) {
+	fn intersection_point(&self) -> Option<Point3<S>> {
+		let (_, ref r) = *self;
+		match self.intersection() {
+			Some((t0, t1)) => {
+				let t = if t0 < zero() { t1 } else { t0 };
+				Some(r.origin.add_v(&r.direction.mul_s(t)))
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Determines the parameters at which a ray enters and exits a capsule, in
+/// that order. If the origin of the ray is inside the capsule, the entry
+/// parameter is negative. Returns `None` if the ray misses the capsule, or if
+/// the capsule lies entirely behind the ray.
+impl<S: BaseFloat> Intersect<Option<(S, S)>> for (Capsule<S>, Ray3<S>) {
+	fn intersection(&self) -> Option<(S, S)> {
+		let (ref cap, ref r) = *self;
+
+		// the capsule is convex, so the ray passes through it in a single
+		// interval, which is the union of the intervals through its body and
+		// through the spheres at either end
+		let body = if cap.axis.length2() > zero() {
+			(Cylinder::new(cap.center, cap.axis, cap.radius), *r).intersection()
+		} else {
+			None
+		};
+		let s0 = Sphere::new(cap.center.add_v(&-cap.axis), cap.radius);
+		let s1 = Sphere::new(cap.center.add_v(&cap.axis), cap.radius);
+		let parts = [body, (s0, *r).intersection(), (s1, *r).intersection()];
+
+		parts.iter().fold(None, |acc, part| match (acc, *part) {
+			(None, part) => part,
+			(acc, None) => acc,
+			(Some((t0, t1)), Some((u0, u1))) => Some((t0.min(u0), t1.max(u1))),
+		})
+	}
+}
+
+/// Determines the first point at which a ray hits the surface of a capsule. If
+/// the origin of the ray is inside the capsule, this is the point at which the
+/// ray leaves it.
+impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Capsule<S>, Ray3<S>) {
+	fn intersection_point(&self) -> Option<Point3<S>> {
+		let (_, ref r) = *self;
+		match self.intersection() {
+			Some((t0, t1)) => {
+				let t = if t0 < zero() { t1 } else { t0 };
+				Some(r.origin.add_v(&r.direction.mul_s(t)))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl<S: BaseFloat> Intersect<bool> for (Capsule<S>, Capsule<S>) {
+	fn intersection(&self) -> bool {
+		let (ref a, ref b) = *self;
+		let (pa, pb) = a.segment().closest_points(&b.segment());
+		let d = pa.sub_p(&pb);
+		let r = a.radius + b.radius;
+		d.length2() <= r * r
+	}
+}
+
+impl<S: BaseFloat> Intersect<bool> for (Capsule<S>, Sphere<S>) {
+	fn intersection(&self) -> bool {
+		let (ref c, ref s) = *self;
+		let d = s.center.sub_p(&c.closest_point_on_segment(&s.center));
+		let r = c.radius + s.radius;
+		d.length2() <= r * r
+	}
+}
+
+/// Determines the ray parameter `t` at which a ray hits a triangle, along with
+/// the barycentric coordinates `(u, v)` of the second and third corners of the
+/// triangle at that point. Both sides of the triangle are tested.
+impl<S: BaseFloat> Intersect<Option<(S, S, S)>> for (Triangle3<S>, Ray3<S>) {
+	fn intersection(&self) -> Option<(S, S, S)> {
+		let (ref tri, ref r) = *self;
+		tri.intersect_ray(r, false)
+	}
+}
+
+impl<S: BaseFloat> IntersectPoint<Option<Point3<S>>> for (Triangle3<S>, Ray3<S>) {
+	fn intersection_point(&self) -> Option<Point3<S>> {
+		let (_, ref r) = *self;
+		match self.intersection() {
+			Some((t, _, _)) => Some(r.origin.add_v(&r.direction.mul_s(t))),
+			_ => None,
+		}
 	}
 }
@@ -15,8 +15,12 @@
 
 //! Line segments
 
-use num::{BaseNum};
+use std::num::{zero, one};
+
+use approx::epsilon;
+use num::{BaseNum, BaseFloat};
 use point::{Point, Point2, Point3};
+use ray::Ray;
 use vector::{Vector};
 
 /// A generic directed line segment from `origin` to `dest`.
@@ -32,5 +36,119 @@ impl<S: BaseNum, V: Vector<S>, P: Point<S, V>>  Line<P> {
     }
 }
 
+impl<S: BaseFloat, V: Vector<S>, P: Point<S, V>> Line<P> {
+    /// Returns the point on the segment that is closest to `p`.
+    pub fn closest_point(&self, p: &P) -> P {
+        let d = self.dest.sub_p(&self.origin);
+        let t = closest_param(&self.origin, &d, Some(one()), p);
+        self.origin.add_v(&d.mul_s(t))
+    }
+
+    /// Returns the squared distance from the segment to `p`.
+    #[inline]
+    pub fn distance2(&self, p: &P) -> S {
+        p.sub_p(&self.closest_point(p)).length2()
+    }
+
+    /// Returns the distance from the segment to `p`.
+    #[inline]
+    pub fn distance(&self, p: &P) -> S {
+        self.distance2(p).sqrt()
+    }
+
+    /// Returns the pair of points, one on each segment, that are closest to
+    /// each other. The first point lies on `self`, and the second on `other`.
+    pub fn closest_points(&self, other: &Line<P>) -> (P, P) {
+        let d1 = self.dest.sub_p(&self.origin);
+        let d2 = other.dest.sub_p(&other.origin);
+        let (s, t) = closest_params(&self.origin, &d1, Some(one()),
+                                    &other.origin, &d2, Some(one()));
+        (self.origin.add_v(&d1.mul_s(s)), other.origin.add_v(&d2.mul_s(t)))
+    }
+
+    /// Returns the pair of points, one on the segment and one on the ray, that
+    /// are closest to each other.
+    pub fn closest_points_ray(&self, ray: &Ray<P, V>) -> (P, P) {
+        let d = self.dest.sub_p(&self.origin);
+        let (s, t) = closest_params(&self.origin, &d, Some(one()),
+                                    &ray.origin, &ray.direction, None);
+        (self.origin.add_v(&d.mul_s(s)), ray.origin.add_v(&ray.direction.mul_s(t)))
+    }
+}
+
+/// The result of intersecting two line segments.
+#[deriving(Clone, PartialEq, Encodable, Decodable)]
+pub enum LineIntersection<P> {
+    /// The segments meet at a single point.
+    Crossing(P),
+    /// The segments are collinear, and overlap along a segment.
+    Overlap(Line<P>),
+}
+
 pub type Line2<S> = Line<Point2<S>>;
 pub type Line3<S> = Line<Point3<S>>;
+
+#[inline]
+fn clamp<S: BaseFloat>(x: S, max: Option<S>) -> S {
+    let x = x.partial_max(zero());
+    match max {
+        Some(max) => x.partial_min(max),
+        None => x,
+    }
+}
+
+/// Computes the parameter `t` of the point on `p0 + t*d` that is closest to
+/// `p`, with `t` restricted to `[0, t_max]`, or to `[0, ∞)` if `t_max` is
+/// `None`.
+pub fn closest_param<S: BaseFloat, V: Vector<S>, P: Point<S, V>>(p0: &P, d: &V, t_max: Option<S>, p: &P) -> S {
+    let len2 = d.length2();
+    if len2 > zero() {
+        clamp(p.sub_p(p0).dot(d) / len2, t_max)
+    } else {
+        zero()
+    }
+}
+
+/// Computes the parameters `(s, t)` of the closest points between `p1 + s*d1`
+/// and `p2 + t*d2`, with `s` restricted to `[0, s_max]` and `t` to
+/// `[0, t_max]`. A maximum of `None` leaves the parameter unbounded, as for a
+/// ray (see _Real Time Collision Detection_, p. 149).
+pub fn closest_params<S: BaseFloat, V: Vector<S>, P: Point<S, V>>(p1: &P, d1: &V, s_max: Option<S>,
+                                                                 p2: &P, d2: &V, t_max: Option<S>) -> (S, S) {
+    let r = p1.sub_p(p2);
+    let a = d1.dot(d1);
+    let e = d2.dot(d2);
+    let f = d2.dot(&r);
+    // the segments are only degenerate relative to the size of the problem,
+    // so compare the squared lengths against the other squared magnitudes
+    let eps: S = epsilon();
+    let eps = eps * eps * (a + e + r.dot(&r));
+
+    if a <= eps && e <= eps {
+        // both degenerate into points
+        (zero(), zero())
+    } else if a <= eps {
+        // the first degenerates into a point
+        (zero(), clamp(f / e, t_max))
+    } else {
+        let c = d1.dot(&r);
+        if e <= eps {
+            // the second degenerates into a point
+            (clamp(-c / a, s_max), zero())
+        } else {
+            let b = d1.dot(d2);
+            let denom = a * e - b * b;
+            // if the lines are parallel, pick an arbitrary `s`
+            let s = if denom != zero() { clamp((b * f - c * e) / denom, s_max) } else { zero() };
+            let t = (b * s + f) / e;
+            // if `t` is out of range, clamp it and recompute `s` for it
+            if t < zero() {
+                return (clamp(-c / a, s_max), zero());
+            }
+            match t_max {
+                Some(t_max) if t > t_max => (clamp((b * t_max - c) / a, s_max), t_max),
+                _ => (s, t),
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loose quadtrees and octrees
+//!
+//! These trees divide a fixed region of space into a hierarchy of cells, each
+//! of which is split into equal halves along every axis. Objects are stored in
+//! the smallest cell that can hold them. The bounds of each cell are
+//! "loosened" to twice their size, so that an object no larger than a cell
+//! can be stored in the cell containing its center, however it straddles the
+//! boundaries between cells. This keeps objects out of the upper levels of
+//! the tree, which every query has to visit.
+
+use std::cmp::{Less, Equal, Greater};
+use std::num::{zero, one};
+
+use aabb::{Aabb, Aabb2, Aabb3};
+use bound::Out;
+use frustum::Frustum;
+use intersect::Intersect;
+use num::{BaseNum, BaseFloat};
+use point::{Point, Point2, Point3};
+use ray::{Ray2, Ray3};
+use sphere::Sphere;
+use vector::{Vector, Vector2, Vector3};
+
+/// Splits a box into equal halves along every axis. This is public so that it
+/// can appear in the bounds of `LooseTree`, but it is not exported from the
+/// crate.
+pub trait Subdivide {
+    /// Returns the parts in the same order as `Aabb2::quadrants` and
+    /// `Aabb3::octants`.
+    fn subdivide(&self) -> Vec<Self>;
+}
+
+impl<S: BaseNum> Subdivide for Aabb2<S> {
+    fn subdivide(&self) -> Vec<Aabb2<S>> {
+        self.quadrants().iter().map(|q| q.clone()).collect()
+    }
+}
+
+impl<S: BaseNum> Subdivide for Aabb3<S> {
+    fn subdivide(&self) -> Vec<Aabb3<S>> {
+        self.octants().iter().map(|o| o.clone()).collect()
+    }
+}
+
+/// A cell of the tree.
+struct Node<A> {
+    cell: A,
+    /// The cell enlarged by half of its size on every side. Every object
+    /// stored below the root lies within the loose bounds of its node.
+    loose: A,
+    /// The indices of the children of the node, if it has been split.
+    children: Vec<uint>,
+    /// The ids of the objects stored in the node.
+    objects: Vec<uint>,
+}
+
+struct Object<A, T> {
+    aabb: A,
+    payload: T,
+    node: uint,
+}
+
+/// A loose tree over a fixed region of space. Each object is identified by
+/// the id returned when it was inserted.
+pub struct LooseTree<S, V, P, A, T> {
+    nodes: Vec<Node<A>>,
+    objects: Vec<Option<Object<A, T>>>,
+    free: Vec<uint>,
+    count: uint,
+    max_depth: uint,
+}
+
+pub type Quadtree<S, T> = LooseTree<S, Vector2<S>, Point2<S>, Aabb2<S>, T>;
+pub type Octree<S, T> = LooseTree<S, Vector3<S>, Point3<S>, Aabb3<S>, T>;
+
+impl<S: BaseNum, V: Vector<S>, P: Point<S, V>, A: Aabb<S, V, P> + Subdivide + Clone, T> LooseTree<S, V, P, A, T> {
+    /// Create an empty tree covering `bounds`, whose cells are split at most
+    /// `max_depth` times. Objects that do not fit inside `bounds` are stored
+    /// at the root, and are tested against every query.
+    pub fn new(bounds: A, max_depth: uint) -> LooseTree<S, V, P, A, T> {
+        let mut nodes = Vec::new();
+        nodes.push(Node { cell: bounds.clone(), loose: bounds, children: Vec::new(), objects: Vec::new() });
+        LooseTree { nodes: nodes, objects: Vec::new(), free: Vec::new(), count: 0, max_depth: max_depth }
+    }
+
+    /// The number of objects in the tree.
+    #[inline]
+    pub fn len(&self) -> uint { self.count }
+
+    /// Returns `true` if the tree contains no objects.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.count == 0 }
+
+    /// Add an object with the bounding box `aabb` to the tree, returning the
+    /// id of the object.
+    pub fn insert(&mut self, aabb: &A, payload: T) -> uint {
+        let node = self.find_node(aabb);
+        let object = Some(Object { aabb: aabb.clone(), payload: payload, node: node });
+        let id = match self.free.pop() {
+            Some(id) => { self.objects.as_mut_slice()[id] = object; id }
+            None => { self.objects.push(object); self.objects.len() - 1 }
+        };
+        self.nodes.as_mut_slice()[node].objects.push(id);
+        self.count += 1;
+        id
+    }
+
+    /// Remove an object from the tree, returning its payload. Fails if `id`
+    /// does not refer to an object in the tree.
+    pub fn remove(&mut self, id: uint) -> T {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        let object = self.objects.as_mut_slice()[id].take().unwrap();
+        {
+            let objects = &mut self.nodes.as_mut_slice()[object.node].objects;
+            let i = objects.iter().position(|&o| o == id).unwrap();
+            objects.swap_remove(i);
+        }
+        self.free.push(id);
+        self.count -= 1;
+        object.payload
+    }
+
+    /// The payload of an object. Fails if `id` does not refer to an object in
+    /// the tree.
+    pub fn get<'a>(&'a self, id: uint) -> &'a T {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        &self.objects[id].as_ref().unwrap().payload
+    }
+
+    /// The bounding box of an object. Fails if `id` does not refer to an
+    /// object in the tree.
+    pub fn aabb<'a>(&'a self, id: uint) -> &'a A {
+        assert!(self.is_object(id), "There is no object in the tree with id {}", id);
+        &self.objects[id].as_ref().unwrap().aabb
+    }
+
+    /// Finds the ids of the objects whose boxes contain a point.
+    pub fn query_point(&self, p: &P) -> Vec<uint> {
+        self.query(|aabb| aabb.contains_inclusive(p))
+    }
+
+    /// Finds the ids of the objects whose boxes overlap `aabb`.
+    pub fn query_aabb(&self, aabb: &A) -> Vec<uint> {
+        self.query(|b| b.overlaps(aabb))
+    }
+
+    /// Finds the ids of the objects whose boxes are within `radius` of
+    /// `center`.
+    pub fn query_radius(&self, center: &P, radius: S) -> Vec<uint> {
+        let r2 = radius * radius;
+        self.query(|aabb| aabb.distance2(center) <= r2)
+    }
+
+    #[inline]
+    fn is_object(&self, id: uint) -> bool {
+        id < self.objects.len() && self.objects[id].is_some()
+    }
+
+    /// Visits the nodes whose loose bounds pass `test`, returning the ids of
+    /// the objects in them whose boxes also pass it.
+    fn query(&self, test: |&A| -> bool) -> Vec<uint> {
+        let mut found = Vec::new();
+        let mut stack = Vec::new();
+        stack.push(0u);
+        loop {
+            let i = match stack.pop() { Some(i) => i, None => break };
+            let node = &self.nodes[i];
+            // the root holds the objects that are outside of the tree, so it
+            // is always visited
+            if i != 0 && !test(&node.loose) { continue; }
+            for &id in node.objects.iter() {
+                if test(&self.objects[id].as_ref().unwrap().aabb) { found.push(id); }
+            }
+            stack.push_all(node.children.as_slice());
+        }
+        found
+    }
+
+    /// Finds the ids of the objects whose boxes are hit by a ray starting at
+    /// `origin`, where `hit` gives the parameter at which the ray hits a box.
+    fn query_ray_by(&self, origin: &P, mut hit: |&A| -> Option<S>) -> Vec<(S, uint)> {
+        let mut hits = Vec::new();
+        for id in self.query(|aabb| hit(aabb).is_some()).move_iter() {
+            let aabb = &self.objects[id].as_ref().unwrap().aabb;
+            let t = if aabb.contains_inclusive(origin) { zero() } else { hit(aabb).unwrap() };
+            hits.push((t, id));
+        }
+        hits.sort_by(|&(a, _), &(b, _)| if a < b { Less } else if a > b { Greater } else { Equal });
+        hits
+    }
+
+    /// Finds the deepest node whose loose bounds contain `aabb`, and that
+    /// contains its center, splitting nodes as needed.
+    fn find_node(&mut self, aabb: &A) -> uint {
+        let center = aabb.center();
+        let mut index = 0u;
+        for _ in range(0, self.max_depth) {
+            let cells = self.nodes[index].cell.subdivide();
+            let k = match cells.iter().position(|cell| cell.contains_inclusive(&center)) {
+                Some(k) => k,
+                None => break,
+            };
+            if !self.loosen(&cells[k]).contains_aabb(aabb) { break; }
+
+            if self.nodes[index].children.is_empty() {
+                let mut children = Vec::new();
+                for cell in cells.move_iter() {
+                    let loose = self.loosen(&cell);
+                    children.push(self.nodes.len());
+                    self.nodes.push(Node { cell: cell, loose: loose, children: Vec::new(), objects: Vec::new() });
+                }
+                self.nodes.as_mut_slice()[index].children = children;
+            }
+            index = self.nodes[index].children[k];
+        }
+        index
+    }
+
+    /// Enlarges a cell by half of its size on every side.
+    fn loosen(&self, cell: &A) -> A {
+        let two = one::<S>() + one::<S>();
+        let half = cell.dim().div_s(two);
+        Aabb::new(cell.min().add_v(&-half), cell.max().add_v(&half))
+    }
+}
+
+impl<S: BaseFloat, T> LooseTree<S, Vector2<S>, Point2<S>, Aabb2<S>, T> {
+    /// Finds the objects whose boxes are hit by a ray. Each object is
+    /// returned along with the ray parameter at which it enters the box, which
+    /// is zero if the box contains the origin of the ray, and the objects are
+    /// sorted from nearest to furthest.
+    pub fn query_ray(&self, ray: &Ray2<S>) -> Vec<(S, uint)> {
+        self.query_ray_by(&ray.origin, |aabb| (ray.clone(), aabb.clone()).intersection())
+    }
+}
+
+impl<S: BaseFloat + 'static, T> LooseTree<S, Vector3<S>, Point3<S>, Aabb3<S>, T> {
+    /// Finds the objects whose boxes are hit by a ray. Each object is
+    /// returned along with the ray parameter at which it enters the box, which
+    /// is zero if the box contains the origin of the ray, and the objects are
+    /// sorted from nearest to furthest.
+    pub fn query_ray(&self, ray: &Ray3<S>) -> Vec<(S, uint)> {
+        self.query_ray_by(&ray.origin, |aabb| (ray.clone(), aabb.clone()).intersection())
+    }
+
+    /// Finds the ids of the objects whose boxes overlap a sphere.
+    pub fn query_sphere(&self, sphere: &Sphere<S>) -> Vec<uint> {
+        self.query_radius(&sphere.center, sphere.radius)
+    }
+
+    /// Finds the ids of the objects whose boxes are inside or cross a
+    /// frustum.
+    pub fn query_frustum(&self, frustum: &Frustum<S>) -> Vec<uint> {
+        self.query(|aabb| frustum.contains(aabb) != Out)
+    }
+}
@@ -19,12 +19,13 @@ use std::fmt;
 use std::mem;
 use std::num::{Zero, zero, One, one, cast};
 
-use angle::{Rad, sin, cos, sin_cos};
-use approx::ApproxEq;
+use angle::{Angle, Rad, sin, cos, sin_cos, atan2};
+use approx::{ApproxEq, epsilon};
 use array::{Array1, Array2, FixedArray};
 use num::{BaseFloat, BaseNum};
 use point::{Point, Point3};
 use quaternion::{Quaternion, ToQuaternion};
+use rotation::{Rotation, Rotation2, Basis2, Basis3};
 use vector::{Vector, EuclideanVector};
 use vector::{Vector2, Vector3, Vector4};
 
@@ -93,6 +94,19 @@ impl<S: BaseFloat + 'static> Matrix2<S> {
         Matrix2::new(cos_theta.clone(),  sin_theta.clone(),
                      -sin_theta.clone(), cos_theta.clone())
     }
+
+    /// Finds the eigenvalues and eigenvectors of a symmetric matrix. The
+    /// eigenvalues are returned in decreasing order, along with a rotation
+    /// whose columns are the corresponding unit eigenvectors.
+    pub fn symmetric_eigen(&self) -> (Vector2<S>, Basis2<S>) {
+        // a single Jacobi rotation diagonalizes the matrix
+        let two = one::<S>() + one::<S>();
+        let (a, b, d) = (self.x.x, self.x.y, self.y.y);
+        let mid = (a + d) / two;
+        let r = ((a - d) * (a - d) / (two * two) + b * b).sqrt();
+        let rot: Basis2<S> = Rotation2::from_angle(atan2(two * b, a - d).div_s(two));
+        (Vector2::new(mid + r, mid - r), rot)
+    }
 }
 
 impl<S: BaseNum> Matrix3<S> {
@@ -215,6 +229,118 @@ Matrix3<S> {
                      zero(),  value.y, zero(),
                      zero(),  zero(),  value.z)
     }
+
+    /// Finds the eigenvalues and eigenvectors of a symmetric matrix, using the
+    /// cyclic Jacobi method. The eigenvalues are returned in decreasing order,
+    /// along with a rotation whose columns are the corresponding unit
+    /// eigenvectors.
+    pub fn symmetric_eigen(&self) -> (Vector3<S>, Basis3<S>) {
+        let two = one::<S>() + one::<S>();
+        let mut a = *self.as_fixed();
+        let mut v = [[zero::<S>(), ..3], ..3];
+        for i in range(0u, 3) { v[i][i] = one(); }
+
+        // stop once the off-diagonal elements are within a few ulps of the
+        // diagonal, or once rounding stops a sweep from reducing them, which
+        // can happen before then for `f32`
+        let ulp: S = Float::epsilon();
+        let tiny = ulp * two * two;
+        let mut last_off: S = Float::infinity();
+        for _ in range(0u, 50) {
+            let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+            let diag = a[0][0] * a[0][0] + a[1][1] * a[1][1] + a[2][2] * a[2][2];
+            if off <= tiny * tiny * diag || off >= last_off { break; }
+            last_off = off;
+
+            for &(p, q) in [(0u, 1u), (0, 2), (1, 2)].iter() {
+                if a[p][q] == zero() { continue; }
+
+                // choose the rotation that zeroes a[p][q]
+                let theta = (a[q][q] - a[p][p]) / (two * a[p][q]);
+                let sign = if theta < zero() { -one::<S>() } else { one::<S>() };
+                let t = sign / (theta.abs() + (theta * theta + one()).sqrt());
+                let c = one::<S>() / (t * t + one()).sqrt();
+                let s = t * c;
+
+                for k in range(0u, 3) {
+                    let (akp, akq) = (a[k][p], a[k][q]);
+                    a[k][p] = c * akp - s * akq;
+                    a[k][q] = s * akp + c * akq;
+                }
+                for k in range(0u, 3) {
+                    let (apk, aqk) = (a[p][k], a[q][k]);
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+                for k in range(0u, 3) {
+                    let (vkp, vkq) = (v[k][p], v[k][q]);
+                    v[k][p] = c * vkp - s * vkq;
+                    v[k][q] = s * vkp + c * vkq;
+                }
+            }
+        }
+
+        // sort the eigenvalues, and make the eigenvectors a right-handed basis
+        let mut order = [0u, 1, 2];
+        for &(i, j) in [(0u, 1u), (1, 2), (0, 1)].iter() {
+            if a[order[j]][order[j]] > a[order[i]][order[i]] { order.swap(i, j); }
+        }
+        let column = |k: uint| Vector3::new(v[0][k], v[1][k], v[2][k]);
+        let (u0, u1) = (column(order[0]), column(order[1]));
+        let basis = Matrix3::from_cols(u0, u1, u0.cross(&u1));
+        (Vector3::new(a[order[0]][order[0]], a[order[1]][order[1]], a[order[2]][order[2]]),
+         Basis3::from_quaternion(&basis.to_quaternion()))
+    }
+
+    /// Computes the singular value decomposition of the matrix, returning
+    /// rotations `u` and `v`, and the singular values `sigma`, such that the
+    /// matrix is equal to `u * diag(sigma) * transpose(v)`.
+    ///
+    /// The singular values are sorted by decreasing magnitude. So that `u`
+    /// and `v` can both be rotations, the last singular value is negative if
+    /// the matrix contains a reflection, that is, if its determinant is
+    /// negative.
+    ///
+    /// The decomposition is found from the eigenvectors of `transpose(m) *
+    /// m`, so the smallest singular values lose precision if the matrix is
+    /// badly conditioned.
+    pub fn svd(&self) -> (Basis3<S>, Vector3<S>, Basis3<S>) {
+        let (values, v) = self.transpose().mul_m(self).symmetric_eigen();
+        let b = self.mul_m(v.as_matrix3());
+
+        // the columns of `b` are orthogonal, with lengths equal to the
+        // singular values, so normalizing them gives the columns of `u`
+        let tolerance = values.x.sqrt() * epsilon();
+        let (s0, s1) = (b.x.length(), b.y.length());
+        let u0 = if s0 > tolerance { b.x.div_s(s0) } else { Vector3::unit_x() };
+        let u1 = b.y.sub_v(&u0.mul_s(u0.dot(&b.y)));
+        let u1 = if s1 > tolerance { u1.normalize() } else { perpendicular(&u0) };
+        let u2 = u0.cross(&u1);
+        let u = Matrix3::from_cols(u0, u1, u2);
+
+        (Basis3::from_quaternion(&u.to_quaternion()), Vector3::new(s0, s1, u2.dot(&b.z)), v)
+    }
+
+    /// Computes the polar decomposition of the matrix, returning a rotation
+    /// `r` and a symmetric stretch matrix `s` such that the matrix is equal to
+    /// `r * s`. The rotation is the closest rotation to the matrix. If the
+    /// matrix contains a reflection, it is kept in the stretch matrix, which
+    /// then has a negative eigenvalue.
+    pub fn polar_decomposition(&self) -> (Basis3<S>, Matrix3<S>) {
+        let (u, sigma, v) = self.svd();
+        let m = v.as_matrix3();
+        let stretch = m.mul_m(&Matrix3::from_diagonal(&sigma)).mul_m(&m.transpose());
+        (u.concat(&v.invert()), stretch)
+    }
+}
+
+/// Returns a unit vector perpendicular to the unit vector `v`.
+fn perpendicular<S: BaseFloat>(v: &Vector3<S>) -> Vector3<S> {
+    let (x, y, z) = (v.x.abs(), v.y.abs(), v.z.abs());
+    let axis = if x <= y && x <= z { Vector3::unit_x() }
+               else if y <= z { Vector3::unit_y() }
+               else { Vector3::unit_z() };
+    v.cross(&axis).normalize()
 }
 
 impl<S: BaseNum> Matrix4<S> {
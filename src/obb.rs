@@ -15,19 +15,325 @@
 
 //! Oriented bounding boxes
 
-use point::{Point2, Point3};
-use vector::{Vector2, Vector3};
+use std::num::{zero, one, abs, cast};
 
+use aabb::{Aabb2, Aabb3};
+use approx::epsilon;
+use array::FixedArray;
+use matrix::{Matrix, Matrix3, Matrix4};
+use num::BaseFloat;
+use point::{Point, Point2, Point3};
+use quaternion::ToQuaternion;
+use rotation::{Rotation, Basis2, Basis3};
+use transform::Transform3;
+use vector::{Vector, Vector2, Vector3, EuclideanVector};
+
+/// A two-dimensional oriented bounding box.
+///
+/// The box is centered on `center`, and its local axes are the columns of the
+/// rotation `rot`. `extents` holds the half-widths of the box along each of
+/// these axes.
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Obb2<S> {
     pub center: Point2<S>,
-    pub axis: Vector2<S>,
+    pub rot: Basis2<S>,
     pub extents: Vector2<S>,
 }
 
+impl<S: BaseFloat + 'static> Obb2<S> {
+    /// Construct a new oriented bounding box.
+    #[inline]
+    pub fn new(center: Point2<S>, rot: Basis2<S>, extents: Vector2<S>) -> Obb2<S> {
+        Obb2 { center: center, rot: rot, extents: extents }
+    }
+
+    /// Construct an oriented bounding box with the same extents as an
+    /// axis-aligned bounding box.
+    #[inline]
+    pub fn from_aabb2(aabb: &Aabb2<S>) -> Obb2<S> {
+        let two = one::<S>() + one::<S>();
+        let half = aabb.max.sub_p(&aabb.min).div_s(two);
+        Obb2::new(aabb.min.add_v(&half), Rotation::identity(), half)
+    }
+
+    /// The local `x` and `y` axes of the box, in that order.
+    #[inline]
+    pub fn axes(&self) -> [Vector2<S>, ..2] {
+        let m = self.rot.as_matrix2();
+        [m.x, m.y]
+    }
+
+    /// The corners of the box, in counter-clockwise order when the rotation
+    /// preserves handedness.
+    pub fn corners(&self) -> [Point2<S>, ..4] {
+        let axes = self.axes();
+        let ex = axes[0].mul_s(self.extents.x);
+        let ey = axes[1].mul_s(self.extents.y);
+        [self.center.add_v(&(-ex).sub_v(&ey)),
+         self.center.add_v(&ex.sub_v(&ey)),
+         self.center.add_v(&ex.add_v(&ey)),
+         self.center.add_v(&(-ex).add_v(&ey))]
+    }
+
+    /// Tests whether a point lies inside or on the boundary of the box.
+    pub fn contains(&self, p: &Point2<S>) -> bool {
+        let d = p.sub_p(&self.center);
+        let axes = self.axes();
+        range(0u, 2).all(|i| abs(d.dot(&axes[i])) <= self.extents[i])
+    }
+
+    /// Returns the point inside or on the boundary of the box that is closest
+    /// to `p`.
+    pub fn closest_point(&self, p: &Point2<S>) -> Point2<S> {
+        let d = p.sub_p(&self.center);
+        let axes = self.axes();
+        let mut q = self.center;
+        for i in range(0u, 2) {
+            let e = self.extents[i];
+            q.add_self_v(&axes[i].mul_s(d.dot(&axes[i]).partial_max(-e).partial_min(e)));
+        }
+        q
+    }
+
+    /// Returns the smallest axis-aligned bounding box that contains this box.
+    pub fn to_aabb2(&self) -> Aabb2<S> {
+        let axes = self.axes();
+        let (ax, ay) = (axes[0], axes[1]);
+        let half = Vector2::new(abs(ax.x) * self.extents.x + abs(ay.x) * self.extents.y,
+                                abs(ax.y) * self.extents.x + abs(ay.y) * self.extents.y);
+        Aabb2::new(self.center.add_v(&-half), self.center.add_v(&half))
+    }
+
+    /// The radius of the projection of the box onto `axis`, which should be of
+    /// unit length.
+    #[inline]
+    fn projected_radius(&self, axis: &Vector2<S>) -> S {
+        let axes = self.axes();
+        self.extents.x * abs(axis.dot(&axes[0])) +
+        self.extents.y * abs(axis.dot(&axes[1]))
+    }
+
+    /// Tests whether two oriented boxes overlap, using the separating axis
+    /// theorem.
+    pub fn intersects(&self, other: &Obb2<S>) -> bool {
+        let t = other.center.sub_p(&self.center);
+        let (a, b) = (self.axes(), other.axes());
+        [a[0], a[1], b[0], b[1]].iter().all(|l| {
+            abs(t.dot(l)) <= self.projected_radius(l) + other.projected_radius(l)
+        })
+    }
+}
+
+/// A three-dimensional oriented bounding box.
+///
+/// The box is centered on `center`, and its local axes are the columns of the
+/// rotation `rot`. `extents` holds the half-widths of the box along each of
+/// these axes.
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Obb3<S> {
     pub center: Point3<S>,
-    pub axis: Vector3<S>,
+    pub rot: Basis3<S>,
     pub extents: Vector3<S>,
 }
+
+impl<S: BaseFloat + 'static> Obb3<S> {
+    /// Construct a new oriented bounding box.
+    #[inline]
+    pub fn new(center: Point3<S>, rot: Basis3<S>, extents: Vector3<S>) -> Obb3<S> {
+        Obb3 { center: center, rot: rot, extents: extents }
+    }
+
+    /// Construct an oriented bounding box with the same extents as an
+    /// axis-aligned bounding box.
+    #[inline]
+    pub fn from_aabb3(aabb: &Aabb3<S>) -> Obb3<S> {
+        let two = one::<S>() + one::<S>();
+        let half = aabb.max.sub_p(&aabb.min).div_s(two);
+        Obb3::new(aabb.min.add_v(&half), Rotation::identity(), half)
+    }
+
+    /// Fits an oriented bounding box to a set of points, using principal
+    /// component analysis. Returns `None` if there are no points.
+    ///
+    /// The axes of the box are the eigenvectors of the covariance matrix of
+    /// the points, sorted so that the points are most spread out along the
+    /// local `x` axis and least along the local `z` axis. This usually gives a
+    /// tight box for elongated point sets, but the box is not guaranteed to be
+    /// the smallest one (see _Real Time Collision Detection_, p. 98).
+    pub fn from_points(points: &[Point3<S>]) -> Option<Obb3<S>> {
+        if points.is_empty() { return None; }
+
+        let n: S = cast(points.len()).unwrap();
+        let mean = points.iter().fold(Vector3::zero(), |sum, p| sum.add_v(&p.to_vec())).div_s(n);
+        let mut cov = [[zero::<S>(), ..3], ..3];
+        for p in points.iter() {
+            let d = p.to_vec().sub_v(&mean);
+            for i in range(0u, 3) {
+                for j in range(0u, 3) {
+                    cov[i][j] = cov[i][j] + d[i] * d[j] / n;
+                }
+            }
+        }
+
+        let (_, rot) = Matrix3::from_fixed_ref(&cov).symmetric_eigen();
+        let m = *rot.as_matrix3();
+        let axes = [m.x, m.y, m.z];
+
+        // find the extent of the points along each axis
+        let mut min = [zero::<S>(), ..3];
+        let mut max = [zero::<S>(), ..3];
+        for p in points.iter() {
+            let d = p.to_vec().sub_v(&mean);
+            for k in range(0u, 3) {
+                let t = d.dot(&axes[k]);
+                min[k] = min[k].partial_min(t);
+                max[k] = max[k].partial_max(t);
+            }
+        }
+
+        let two = one::<S>() + one::<S>();
+        let mut center = Point::from_vec(&mean);
+        for k in range(0u, 3) {
+            center.add_self_v(&axes[k].mul_s((min[k] + max[k]) / two));
+        }
+        let extents = Vector3::new((max[0] - min[0]) / two,
+                                   (max[1] - min[1]) / two,
+                                   (max[2] - min[2]) / two);
+        Some(Obb3::new(center, rot, extents))
+    }
+
+    /// Transforms the box by an affine transformation matrix. If the
+    /// transformation preserves the right angles between the axes of the box,
+    /// as rotations, translations and uniform scales do, the result is exact.
+    /// Otherwise the transformed box is a parallelepiped, and the result is a
+    /// box that contains it, oriented along its first two edges.
+    pub fn transform_matrix4(&self, m: &Matrix4<S>) -> Obb3<S> {
+        let center = Point3::from_homogeneous(&m.mul_v(&self.center.to_homogeneous()));
+        let linear = Matrix3::from_cols(m.x.truncate(), m.y.truncate(), m.z.truncate());
+        let axes = self.axes();
+        let d = [linear.mul_v(&axes[0]), linear.mul_v(&axes[1]), linear.mul_v(&axes[2])];
+
+        // find a right-handed orthonormal frame aligned with the transformed
+        // axes, using the Gram–Schmidt process
+        let u0 = d[0].normalize();
+        let u1 = d[1].sub_v(&u0.mul_s(d[1].dot(&u0))).normalize();
+        let u2 = u0.cross(&u1);
+        let rot = Basis3::from_quaternion(&Matrix3::from_cols(u0, u1, u2).to_quaternion());
+
+        // project the transformed half-widths onto the new axes
+        let e = self.extents;
+        let half = [d[0].mul_s(e.x), d[1].mul_s(e.y), d[2].mul_s(e.z)];
+        let extent = |u: &Vector3<S>| abs(u.dot(&half[0])) + abs(u.dot(&half[1])) + abs(u.dot(&half[2]));
+        Obb3::new(center, rot, Vector3::new(extent(&u0), extent(&u1), extent(&u2)))
+    }
+
+    /// Transforms the box, returning a box that contains the result.
+    #[inline]
+    pub fn transform<T: Transform3<S>>(&self, t: &T) -> Obb3<S> {
+        self.transform_matrix4(&t.to_matrix4())
+    }
+
+    /// The local `x`, `y` and `z` axes of the box, in that order.
+    #[inline]
+    pub fn axes(&self) -> [Vector3<S>, ..3] {
+        let m = self.rot.as_matrix3();
+        [m.x, m.y, m.z]
+    }
+
+    /// The corners of the box. Corner `i` lies on the positive side of local
+    /// axis `k` if bit `k` of `i` is set.
+    pub fn corners(&self) -> [Point3<S>, ..8] {
+        let axes = self.axes();
+        let ex = axes[0].mul_s(self.extents.x);
+        let ey = axes[1].mul_s(self.extents.y);
+        let ez = axes[2].mul_s(self.extents.z);
+        let corner = |sx: S, sy: S, sz: S| {
+            self.center.add_v(&ex.mul_s(sx)).add_v(&ey.mul_s(sy)).add_v(&ez.mul_s(sz))
+        };
+        let (n, p) = (-one::<S>(), one::<S>());
+        [corner(n, n, n), corner(p, n, n), corner(n, p, n), corner(p, p, n),
+         corner(n, n, p), corner(p, n, p), corner(n, p, p), corner(p, p, p)]
+    }
+
+    /// Tests whether a point lies inside or on the boundary of the box.
+    pub fn contains(&self, p: &Point3<S>) -> bool {
+        let d = p.sub_p(&self.center);
+        let axes = self.axes();
+        range(0u, 3).all(|i| abs(d.dot(&axes[i])) <= self.extents[i])
+    }
+
+    /// Returns the point inside or on the boundary of the box that is closest
+    /// to `p`.
+    pub fn closest_point(&self, p: &Point3<S>) -> Point3<S> {
+        let d = p.sub_p(&self.center);
+        let axes = self.axes();
+        let mut q = self.center;
+        for i in range(0u, 3) {
+            let e = self.extents[i];
+            q.add_self_v(&axes[i].mul_s(d.dot(&axes[i]).partial_max(-e).partial_min(e)));
+        }
+        q
+    }
+
+    /// Returns the smallest axis-aligned bounding box that contains this box.
+    pub fn to_aabb3(&self) -> Aabb3<S> {
+        let axes = self.axes();
+        let (ax, ay, az) = (axes[0], axes[1], axes[2]);
+        let e = self.extents;
+        let half = Vector3::new(abs(ax.x) * e.x + abs(ay.x) * e.y + abs(az.x) * e.z,
+                                abs(ax.y) * e.x + abs(ay.y) * e.y + abs(az.y) * e.z,
+                                abs(ax.z) * e.x + abs(ay.z) * e.y + abs(az.z) * e.z);
+        Aabb3::new(self.center.add_v(&-half), self.center.add_v(&half))
+    }
+
+    /// Tests whether two oriented boxes overlap, using the separating axis
+    /// theorem. The fifteen candidate axes are the face normals of both boxes,
+    /// and the cross products of each pair of edge directions (see _Real Time
+    /// Collision Detection_, p. 103).
+    pub fn intersects(&self, other: &Obb3<S>) -> bool {
+        let a = self.axes();
+        let b = other.axes();
+        let (ea, eb) = (self.extents, other.extents);
+
+        // express the other box in the local frame of this one, padding the
+        // absolute values to avoid false negatives when two edges are
+        // near parallel and their cross product is close to zero
+        let mut r = [[zero::<S>(), ..3], ..3];
+        let mut abs_r = [[zero::<S>(), ..3], ..3];
+        for i in range(0u, 3) {
+            for j in range(0u, 3) {
+                r[i][j] = a[i].dot(&b[j]);
+                abs_r[i][j] = abs(r[i][j]) + epsilon();
+            }
+        }
+        let d = other.center.sub_p(&self.center);
+        let t = Vector3::new(d.dot(&a[0]), d.dot(&a[1]), d.dot(&a[2]));
+
+        // the axes of this box
+        for i in range(0u, 3) {
+            let ra = ea[i];
+            let rb = eb.x * abs_r[i][0] + eb.y * abs_r[i][1] + eb.z * abs_r[i][2];
+            if abs(t[i]) > ra + rb { return false; }
+        }
+
+        // the axes of the other box
+        for j in range(0u, 3) {
+            let ra = ea.x * abs_r[0][j] + ea.y * abs_r[1][j] + ea.z * abs_r[2][j];
+            let rb = eb[j];
+            if abs(t.x * r[0][j] + t.y * r[1][j] + t.z * r[2][j]) > ra + rb { return false; }
+        }
+
+        // the cross products of the axes of both boxes
+        for i in range(0u, 3) {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in range(0u, 3) {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                if abs(t[i2] * r[i1][j] - t[i1] * r[i2][j]) > ra + rb { return false; }
+            }
+        }
+
+        true
+    }
+}
@@ -16,13 +16,16 @@
 use std::num::one;
 use std::fmt;
 
-use approx::ApproxEq;
+use approx::{ApproxEq, epsilon};
+use bound::{Bound, Relation, In, Cross, Out};
+use matrix::{Matrix, Matrix4};
 use num::BaseFloat;
 use point::{Point, Point3};
+use transform::Transform3;
 use vector::{EuclideanVector, Vector, Vector3, Vector4};
 
 
-/// A 3-dimensional plane formed from the equation: `A*x + B*y + C*z - D = 0`.
+/// A 3-dimensional plane formed from the equation: `A*x + B*y + C*z + D = 0`.
 ///
 /// # Fields
 ///
@@ -34,9 +37,10 @@ use vector::{EuclideanVector, Vector, Vector3, Vector4};
 ///
 /// # Notes
 ///
-/// The `A*x + B*y + C*z - D = 0` form is preferred over the other common
-/// alternative, `A*x + B*y + C*z + D = 0`, because it tends to avoid
-/// superfluous negations (see _Real Time Collision Detection_, p. 55).
+/// The `A*x + B*y + C*z + D = 0` form matches the planes extracted from
+/// projection matrices (see `Frustum::from_matrix4`), and is the form assumed
+/// by the intersection tests. Points for which `A*x + B*y + C*z + D` is
+/// positive lie in front of the plane, on the side that `n` points towards.
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Plane<S> {
     pub n: Vector3<S>,
@@ -89,7 +93,65 @@ impl<S: BaseFloat> Plane<S> {
     /// Construct a plane from a point and a normal vector.
     /// The plane will contain the point `p` and be perpendicular to `n`.
     pub fn from_point_normal(p: Point3<S>, n: Vector3<S>) -> Plane<S> {
-        Plane { n: n, d: p.dot(&n) }
+        Plane { n: n, d: -p.dot(&n) }
+    }
+
+    /// Returns the signed distance from the plane to the point `p`. This is
+    /// positive if the point is in front of the plane, and negative if it is
+    /// behind it. If `n` is not of unit length, the result is scaled by its
+    /// length.
+    #[inline]
+    pub fn distance(&self, p: &Point3<S>) -> S {
+        p.dot(&self.n) + self.d
+    }
+
+    /// Classify a point with respect to the plane. The point is `In` if it is
+    /// in front of the plane, `Out` if it is behind it, and `Cross` if it lies
+    /// within `epsilon` of the plane.
+    #[inline]
+    pub fn relate_point_eps(&self, p: &Point3<S>, epsilon: &S) -> Relation {
+        let dist = self.distance(p);
+        if dist > *epsilon { In }
+        else if dist < -*epsilon { Out }
+        else { Cross }
+    }
+
+    /// Classify a point with respect to the plane, using the default epsilon
+    /// for `S` as the thickness of the plane.
+    #[inline]
+    pub fn relate_point(&self, p: &Point3<S>) -> Relation {
+        self.relate_point_eps(p, &epsilon())
+    }
+
+    /// Classify a bound with respect to the plane. The bound is `In` if it
+    /// lies entirely in front of the plane, `Out` if it lies entirely behind
+    /// it, and `Cross` if it straddles it.
+    #[inline]
+    pub fn relate<B: Bound<S>>(&self, bound: &B) -> Relation {
+        bound.relate_plane(self)
+    }
+
+    /// Returns the point on the plane that is closest to `p`.
+    #[inline]
+    pub fn project_point(&self, p: &Point3<S>) -> Point3<S> {
+        let t = self.distance(p) / self.n.length2();
+        p.add_v(&self.n.mul_s(-t))
+    }
+
+    /// Returns the mirror image of `p` on the other side of the plane.
+    #[inline]
+    pub fn reflect_point(&self, p: &Point3<S>) -> Point3<S> {
+        let t = self.distance(p) / self.n.length2();
+        p.add_v(&self.n.mul_s(-(t + t)))
+    }
+
+    /// Returns the mirror image of the direction `v` with respect to the
+    /// plane. The component of `v` along the normal is negated, while the
+    /// components parallel to the plane are unchanged.
+    #[inline]
+    pub fn reflect_vector(&self, v: &Vector3<S>) -> Vector3<S> {
+        let t = v.dot(&self.n) / self.n.length2();
+        v.sub_v(&self.n.mul_s(t + t))
     }
 
 	/// Returns an approximately equal plane, but with `n` having a `length` (or
@@ -148,6 +210,27 @@ impl<S: BaseFloat> Plane<S> {
 	}
 }
 
+impl<S: BaseFloat + 'static> Plane<S> {
+    /// Transforms the plane by an affine transformation matrix, by multiplying
+    /// its coefficients by the inverse transpose of the matrix. The normal of
+    /// the result has the same length as `n`. Returns `None` if the matrix
+    /// cannot be inverted.
+    pub fn transform_matrix4(&self, m: &Matrix4<S>) -> Option<Plane<S>> {
+        m.invert().map(|inv| {
+            let p = Vector4::new(self.n.x, self.n.y, self.n.z, self.d);
+            let q = Vector4::new(inv.x.dot(&p), inv.y.dot(&p), inv.z.dot(&p), inv.w.dot(&p));
+            Plane::from_vector4(q).normalize_normal_to(self.n.length())
+        })
+    }
+
+    /// Transforms the plane. Returns `None` if the transformation cannot be
+    /// inverted.
+    #[inline]
+    pub fn transform<T: Transform3<S>>(&self, t: &T) -> Option<Plane<S>> {
+        self.transform_matrix4(&t.to_matrix4())
+    }
+}
+
 impl<S: BaseFloat + ApproxEq<S>>
 ApproxEq<S> for Plane<S> {
     #[inline]
@@ -158,7 +241,7 @@ ApproxEq<S> for Plane<S> {
 
 impl<S: BaseFloat> fmt::Show for Plane<S> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:f}x + {:f}y + {:f}z - {:f} = 0",
+        write!(f, "{:f}x + {:f}y + {:f}z + {:f} = 0",
                self.n.x, self.n.y, self.n.z, self.d)
     }
 }
@@ -28,11 +28,11 @@ use num::{BaseNum, BaseFloat};
 use vector::*;
 
 /// A point in 2-dimensional space.
-#[deriving(PartialEq, Clone, Hash, Encodable, Decodable)]
+#[deriving(PartialEq, Eq, Clone, Hash, Encodable, Decodable)]
 pub struct Point2<S> { pub x: S, pub y: S }
 
 /// A point in 3-dimensional space.
-#[deriving(PartialEq, Clone, Hash, Encodable, Decodable)]
+#[deriving(PartialEq, Eq, Clone, Hash, Encodable, Decodable)]
 pub struct Point3<S> { pub x: S, pub y: S, pub z: S }
 
 
@@ -16,10 +16,11 @@
 use std::num::{zero, one, cast};
 
 use angle::{Angle, tan, cot};
-use frustum::Frustum;
+use frustum::{Frustum, FrustumPoints};
 use matrix::{Matrix4, ToMatrix4};
 use num::BaseFloat;
 use plane::Plane;
+use point::Point3;
 
 /// Create a perspective projection matrix.
 ///
@@ -92,6 +93,12 @@ impl<S: BaseFloat, A: Angle<S>> PerspectiveFov<S, A> {
             far:     self.far.clone(),
         }
     }
+
+    /// Computes the corners of the view volume in view space.
+    #[inline]
+    pub fn to_frustum_points(&self) -> FrustumPoints<S> {
+        self.to_perspective().to_frustum_points()
+    }
 }
 
 impl<S: BaseFloat + 'static, A: Angle<S>> Projection<S> for PerspectiveFov<S, A> {
@@ -150,6 +157,24 @@ pub struct Perspective<S> {
     pub near:   S,  far:    S,
 }
 
+impl<S: BaseFloat> Perspective<S> {
+    /// Computes the corners of the view volume in view space.
+    pub fn to_frustum_points(&self) -> FrustumPoints<S> {
+        // the far rectangle is the near rectangle scaled away from the eye
+        let s = self.far / self.near;
+        FrustumPoints {
+            near_top_left:     Point3::new(self.left,      self.top,        -self.near),
+            near_top_right:    Point3::new(self.right,     self.top,        -self.near),
+            near_bottom_left:  Point3::new(self.left,      self.bottom,     -self.near),
+            near_bottom_right: Point3::new(self.right,     self.bottom,     -self.near),
+            far_top_left:      Point3::new(self.left * s,  self.top * s,    -self.far),
+            far_top_right:     Point3::new(self.right * s, self.top * s,    -self.far),
+            far_bottom_left:   Point3::new(self.left * s,  self.bottom * s, -self.far),
+            far_bottom_right:  Point3::new(self.right * s, self.bottom * s, -self.far),
+        }
+    }
+}
+
 impl<S: BaseFloat + 'static> Projection<S> for Perspective<S> {
     fn to_frustum(&self) -> Frustum<S> {
         // TODO: Could this be faster?
@@ -200,15 +225,31 @@ pub struct Ortho<S> {
     pub near:   S,  far:    S,
 }
 
+impl<S: BaseFloat> Ortho<S> {
+    /// Computes the corners of the view volume in view space.
+    pub fn to_frustum_points(&self) -> FrustumPoints<S> {
+        FrustumPoints {
+            near_top_left:     Point3::new(self.left,  self.top,    -self.near),
+            near_top_right:    Point3::new(self.right, self.top,    -self.near),
+            near_bottom_left:  Point3::new(self.left,  self.bottom, -self.near),
+            near_bottom_right: Point3::new(self.right, self.bottom, -self.near),
+            far_top_left:      Point3::new(self.left,  self.top,    -self.far),
+            far_top_right:     Point3::new(self.right, self.top,    -self.far),
+            far_bottom_left:   Point3::new(self.left,  self.bottom, -self.far),
+            far_bottom_right:  Point3::new(self.right, self.bottom, -self.far),
+        }
+    }
+}
+
 impl<S: BaseFloat> Projection<S> for Ortho<S> {
     fn to_frustum(&self) -> Frustum<S> {
         Frustum {
-            left:   Plane::from_abcd( one::<S>(), zero::<S>(), zero::<S>(), self.left.clone()),
-            right:  Plane::from_abcd(-one::<S>(), zero::<S>(), zero::<S>(), self.right.clone()),
-            bottom: Plane::from_abcd(zero::<S>(),  one::<S>(), zero::<S>(), self.bottom.clone()),
-            top:    Plane::from_abcd(zero::<S>(), -one::<S>(), zero::<S>(), self.top.clone()),
-            near:   Plane::from_abcd(zero::<S>(), zero::<S>(), -one::<S>(), self.near.clone()),
-            far:    Plane::from_abcd(zero::<S>(), zero::<S>(),  one::<S>(), self.far.clone()),
+            left:   Plane::from_abcd( one::<S>(), zero::<S>(), zero::<S>(), -self.left),
+            right:  Plane::from_abcd(-one::<S>(), zero::<S>(), zero::<S>(),  self.right),
+            bottom: Plane::from_abcd(zero::<S>(),  one::<S>(), zero::<S>(), -self.bottom),
+            top:    Plane::from_abcd(zero::<S>(), -one::<S>(), zero::<S>(),  self.top),
+            near:   Plane::from_abcd(zero::<S>(), zero::<S>(), -one::<S>(), -self.near),
+            far:    Plane::from_abcd(zero::<S>(), zero::<S>(),  one::<S>(),  self.far),
         }
     }
 }
@@ -13,7 +13,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use num::BaseNum;
+use line::{Line, closest_param, closest_params};
+use num::{BaseNum, BaseFloat};
 use point::{Point, Point2, Point3};
 use vector::{Vector, Vector2, Vector3};
 
@@ -31,5 +32,41 @@ impl<S: BaseNum, V: Vector<S>, P: Point<S, V>> Ray<P, V> {
     }
 }
 
+impl<S: BaseFloat, V: Vector<S>, P: Point<S, V>> Ray<P, V> {
+    /// Returns the point on the ray that is closest to `p`.
+    pub fn closest_point(&self, p: &P) -> P {
+        let t = closest_param(&self.origin, &self.direction, None, p);
+        self.origin.add_v(&self.direction.mul_s(t))
+    }
+
+    /// Returns the squared distance from the ray to `p`.
+    #[inline]
+    pub fn distance2(&self, p: &P) -> S {
+        p.sub_p(&self.closest_point(p)).length2()
+    }
+
+    /// Returns the distance from the ray to `p`.
+    #[inline]
+    pub fn distance(&self, p: &P) -> S {
+        self.distance2(p).sqrt()
+    }
+
+    /// Returns the pair of points, one on each ray, that are closest to each
+    /// other. The first point lies on `self`, and the second on `other`.
+    pub fn closest_points(&self, other: &Ray<P, V>) -> (P, P) {
+        let (s, t) = closest_params(&self.origin, &self.direction, None,
+                                    &other.origin, &other.direction, None);
+        (self.origin.add_v(&self.direction.mul_s(s)), other.origin.add_v(&other.direction.mul_s(t)))
+    }
+
+    /// Returns the pair of points, one on the ray and one on the segment, that
+    /// are closest to each other.
+    #[inline]
+    pub fn closest_points_line(&self, line: &Line<P>) -> (P, P) {
+        let (q, p) = line.closest_points_ray(self);
+        (p, q)
+    }
+}
+
 pub type Ray2<S> = Ray<Point2<S>, Vector2<S>>;
 pub type Ray3<S> = Ray<Point3<S>, Vector3<S>>;
@@ -0,0 +1,334 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Uniform grids, stored as spatial hashes
+//!
+//! Space is divided into square or cubic cells of a fixed size, identified by
+//! integer coordinates. Only the cells that contain objects are stored, in a
+//! hash map, so the grid is unbounded.
+
+use std::collections::{HashMap, HashSet};
+use std::cmp::{Less, Equal, Greater};
+use std::num::{zero, cast};
+
+use aabb::{Aabb, Aabb2, Aabb3};
+use array::Array1;
+use intersect::Intersect;
+use num::BaseFloat;
+use point::{Point, Point2, Point3};
+use ray::{Ray2, Ray3};
+use vector::{Vector2, Vector3};
+
+macro_rules! spatial_hash(
+    ($Self:ident, $Point:ident, $Vector:ident, $Aabb:ident, $Ray:ident, $n:expr) => (
+        /// A spatial hash of points or boxes. Each object is identified by the
+        /// id returned when it was inserted.
+        pub struct $Self<S, T> {
+            cell_size: S,
+            cells: HashMap<$Point<int>, Vec<uint>>,
+            objects: Vec<Option<($Aabb<S>, T)>>,
+            free: Vec<uint>,
+            count: uint,
+        }
+
+        impl<S: BaseFloat, T> $Self<S, T> {
+            /// Create an empty grid whose cells are `cell_size` wide.
+            pub fn new(cell_size: S) -> $Self<S, T> {
+                $Self {
+                    cell_size: cell_size,
+                    cells: HashMap::new(),
+                    objects: Vec::new(),
+                    free: Vec::new(),
+                    count: 0,
+                }
+            }
+
+            /// The width of the cells of the grid.
+            #[inline]
+            pub fn cell_size(&self) -> S { self.cell_size }
+
+            /// The number of objects in the grid.
+            #[inline]
+            pub fn len(&self) -> uint { self.count }
+
+            /// Returns `true` if the grid contains no objects.
+            #[inline]
+            pub fn is_empty(&self) -> bool { self.count == 0 }
+
+            /// The coordinates of the cell containing a point. Fails if a
+            /// coordinate divided by the cell size does not fit in an `int`,
+            /// as it may for very distant points or a very small `cell_size`.
+            pub fn cell(&self, p: &$Point<S>) -> $Point<int> {
+                let mut cell: $Point<int> = Point::origin();
+                for k in range(0u, $n) {
+                    cell[k] = cast((p[k] / self.cell_size).floor()).unwrap();
+                }
+                cell
+            }
+
+            /// Add a point to the grid, returning the id of the object.
+            #[inline]
+            pub fn insert(&mut self, p: &$Point<S>, payload: T) -> uint {
+                self.insert_aabb(&$Aabb::new(*p, *p), payload)
+            }
+
+            /// Add an object with the bounding box `aabb` to the grid,
+            /// returning the id of the object. The object is stored in every
+            /// cell that the box overlaps.
+            pub fn insert_aabb(&mut self, aabb: &$Aabb<S>, payload: T) -> uint {
+                let id = match self.free.pop() {
+                    Some(id) => { self.objects.as_mut_slice()[id] = Some((aabb.clone(), payload)); id }
+                    None => { self.objects.push(Some((aabb.clone(), payload))); self.objects.len() - 1 }
+                };
+                let (min, max) = (self.cell(&aabb.min), self.cell(&aabb.max));
+                let cells = &mut self.cells;
+                for_cells(&min, &max, $n, |cell| {
+                    cells.find_or_insert_with(cell, |_| Vec::new()).push(id);
+                });
+                self.count += 1;
+                id
+            }
+
+            /// Remove an object from the grid, returning its payload. Fails if
+            /// `id` does not refer to an object in the grid.
+            pub fn remove(&mut self, id: uint) -> T {
+                assert!(self.is_object(id), "There is no object in the grid with id {}", id);
+                let (aabb, payload) = self.objects.as_mut_slice()[id].take().unwrap();
+                let (min, max) = (self.cell(&aabb.min), self.cell(&aabb.max));
+                let cells = &mut self.cells;
+                for_cells(&min, &max, $n, |cell| {
+                    let empty = {
+                        let ids = cells.get_mut(&cell);
+                        let i = ids.iter().position(|&other| other == id).unwrap();
+                        ids.swap_remove(i);
+                        ids.is_empty()
+                    };
+                    if empty { cells.remove(&cell); }
+                });
+                self.free.push(id);
+                self.count -= 1;
+                payload
+            }
+
+            /// The payload of an object. Fails if `id` does not refer to an
+            /// object in the grid.
+            pub fn get<'a>(&'a self, id: uint) -> &'a T {
+                assert!(self.is_object(id), "There is no object in the grid with id {}", id);
+                self.objects[id].as_ref().unwrap().ref1()
+            }
+
+            /// The bounding box of an object, which is empty for a point.
+            /// Fails if `id` does not refer to an object in the grid.
+            pub fn aabb<'a>(&'a self, id: uint) -> &'a $Aabb<S> {
+                assert!(self.is_object(id), "There is no object in the grid with id {}", id);
+                self.objects[id].as_ref().unwrap().ref0()
+            }
+
+            /// The ids of the objects stored in a cell, or `None` if the cell
+            /// is empty.
+            pub fn objects_in_cell<'a>(&'a self, cell: &$Point<int>) -> Option<&'a [uint]> {
+                self.cells.find(cell).map(|ids| ids.as_slice())
+            }
+
+            /// Finds the ids of the objects that are within `radius` of
+            /// `center`.
+            pub fn query_radius(&self, center: &$Point<S>, radius: S) -> Vec<uint> {
+                let r = $Vector::from_value(radius);
+                let min = self.cell(&center.add_v(&-r));
+                let max = self.cell(&center.add_v(&r));
+                let mut found = Vec::new();
+                for_cells(&min, &max, $n, |cell| {
+                    for ids in self.objects_in_cell(&cell).iter() {
+                        for &id in ids.iter() {
+                            if self.aabb(id).distance2(center) <= radius * radius { found.push(id); }
+                        }
+                    }
+                });
+                // objects that span several cells are found more than once
+                found.sort();
+                found.dedup();
+                found
+            }
+
+            /// Finds the `k` objects nearest to `p`, along with their
+            /// distances from it, sorted from nearest to furthest.
+            ///
+            /// The cells are searched in rings of increasing size around the
+            /// cell containing `p`, until the objects that have been found are
+            /// nearer than anything outside of the rings could be. Once the
+            /// rings span more cells than are occupied, the remaining objects
+            /// are tested directly instead, so distant objects do not make the
+            /// search visit every empty cell between them and `p`.
+            pub fn nearest(&self, p: &$Point<S>, k: uint) -> Vec<(S, uint)> {
+                let mut found = Vec::new();
+                if k == 0 { return found; }
+
+                let center = self.cell(p);
+                let mut seen = HashSet::new();
+                let mut ring = 0i;
+                while seen.len() < self.count {
+                    let width = (2 * ring + 1) as uint;
+                    if range(0u, $n).fold(1u, |cells, _| cells * width) > self.cells.len() {
+                        for (id, object) in self.objects.iter().enumerate() {
+                            match *object {
+                                Some((ref aabb, _)) if !seen.contains(&id) => found.push((aabb.distance(p), id)),
+                                _ => (),
+                            }
+                        }
+                        found.sort_by(|&(a, _), &(b, _)| if a < b { Less } else if a > b { Greater } else { Equal });
+                        break;
+                    }
+
+                    let mut min = center;
+                    let mut max = center;
+                    for i in range(0u, $n) {
+                        min[i] = center[i] - ring;
+                        max[i] = center[i] + ring;
+                    }
+                    for_cells(&min, &max, $n, |cell| {
+                        // skip the cells inside the ring, which were searched
+                        // before
+                        if range(0u, $n).all(|i| cell[i] > min[i] && cell[i] < max[i]) { return; }
+                        for ids in self.objects_in_cell(&cell).iter() {
+                            for &id in ids.iter() {
+                                if seen.insert(id) { found.push((self.aabb(id).distance(p), id)); }
+                            }
+                        }
+                    });
+                    found.sort_by(|&(a, _), &(b, _)| if a < b { Less } else if a > b { Greater } else { Equal });
+
+                    // the nearest that an object outside of the rings could be
+                    let mut reach: S = Float::infinity();
+                    for i in range(0u, $n) {
+                        let lower: S = cast(min[i]).unwrap();
+                        let upper: S = cast(max[i] + 1).unwrap();
+                        reach = reach.min(p[i] - lower * self.cell_size)
+                                     .min(upper * self.cell_size - p[i]);
+                    }
+                    if found.len() >= k && found[k - 1].val0() <= reach { break; }
+                    ring += 1;
+                }
+                found.truncate(k);
+                found
+            }
+
+            /// Visits the cells that a ray passes through, in order, along
+            /// with the ray parameter at which it enters each of them. The
+            /// traversal stops at the first cell entered after `t_max`, or
+            /// when `f` returns `false`, so if `t_max` is infinite it is up to
+            /// `f` to end it.
+            ///
+            /// The cells are found with a digital differential analyzer, which
+            /// steps to whichever neighbouring cell the ray reaches first (see
+            /// _A Fast Voxel Traversal Algorithm for Ray Tracing_, Amanatides
+            /// and Woo, 1987).
+            pub fn traverse_ray(&self, ray: &$Ray<S>, t_max: S, f: |&$Point<int>, S| -> bool) {
+                let mut cell = self.cell(&ray.origin);
+                let mut step = [0i, ..$n];
+                let mut t_next: [S, ..$n] = [Float::infinity(), ..$n];
+                let mut t_delta: [S, ..$n] = [Float::infinity(), ..$n];
+                for i in range(0u, $n) {
+                    let d = ray.direction[i];
+                    if d > zero() {
+                        let boundary: S = cast(cell[i] + 1).unwrap();
+                        step[i] = 1;
+                        t_next[i] = (boundary * self.cell_size - ray.origin[i]) / d;
+                        t_delta[i] = self.cell_size / d;
+                    } else if d < zero() {
+                        let boundary: S = cast(cell[i]).unwrap();
+                        step[i] = -1;
+                        t_next[i] = (boundary * self.cell_size - ray.origin[i]) / d;
+                        t_delta[i] = -self.cell_size / d;
+                    }
+                }
+
+                let mut t = zero();
+                loop {
+                    if !f(&cell, t) { return; }
+
+                    // step across the nearest cell boundary
+                    let mut axis = 0u;
+                    for i in range(1u, $n) {
+                        if t_next[i] < t_next[axis] { axis = i; }
+                    }
+                    // a ray with no direction stays in its first cell
+                    if step[axis] == 0 { return; }
+                    t = t_next[axis];
+                    if t > t_max { return; }
+                    cell[axis] = cell[axis] + step[axis];
+                    t_next[axis] = t_next[axis] + t_delta[axis];
+                }
+            }
+
+            /// Finds the objects that are hit by a ray before `t_max`, which
+            /// should be finite. Each object is returned along with the ray
+            /// parameter at which it enters the object's box, which is zero if
+            /// the box contains the origin of the ray, and the objects are
+            /// sorted from nearest to furthest.
+            pub fn query_ray(&self, ray: &$Ray<S>, t_max: S) -> Vec<(S, uint)> {
+                let mut hits = Vec::new();
+                let mut seen = HashSet::new();
+                self.traverse_ray(ray, t_max, |cell, _| {
+                    for ids in self.objects_in_cell(cell).iter() {
+                        for &id in ids.iter() {
+                            if !seen.insert(id) { continue; }
+                            let aabb = self.aabb(id);
+                            let t = if aabb.contains_inclusive(&ray.origin) {
+                                Some(zero())
+                            } else {
+                                (ray.clone(), aabb.clone()).intersection()
+                            };
+                            match t {
+                                Some(t) if t <= t_max => hits.push((t, id)),
+                                _ => (),
+                            }
+                        }
+                    }
+                    true
+                });
+                hits.sort_by(|&(a, _), &(b, _)| if a < b { Less } else if a > b { Greater } else { Equal });
+                hits
+            }
+
+            #[inline]
+            fn is_object(&self, id: uint) -> bool {
+                id < self.objects.len() && self.objects[id].is_some()
+            }
+        }
+    )
+)
+
+spatial_hash!(SpatialHash2, Point2, Vector2, Aabb2, Ray2, 2)
+spatial_hash!(SpatialHash3, Point3, Vector3, Aabb3, Ray3, 3)
+
+/// Calls `f` with the coordinates of every cell in the box of cells from `min`
+/// to `max` inclusive, where the coordinates have `n` components.
+fn for_cells<C: Array1<int> + Clone>(min: &C, max: &C, n: uint, f: |C|) {
+    let mut cell = min.clone();
+    loop {
+        f(cell.clone());
+        // advance the coordinates like an odometer
+        let mut i = 0u;
+        loop {
+            if i == n { return; }
+            if cell[i] < max[i] {
+                cell[i] = cell[i] + 1;
+                break;
+            }
+            cell[i] = min[i];
+            i += 1;
+        }
+    }
+}
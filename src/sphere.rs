@@ -15,10 +15,350 @@
 
 //! Bounding sphere
 
-use point::Point3;
+use std::num::{zero, one, abs};
+
+use aabb::{Aabb, Aabb3};
+use approx::epsilon;
+use capsule::Capsule;
+use intersect::Intersect;
+use matrix::{Matrix, Matrix3, Matrix4};
+use num::BaseFloat;
+use plane::Plane;
+use point::{Point, Point3};
+use ray::{Ray, Ray3};
+use transform::Transform3;
+use triangle::Triangle3;
+use vector::{Vector, Vector3, EuclideanVector};
 
 #[deriving(Clone, PartialEq, Encodable, Decodable)]
 pub struct Sphere<S> {
     pub center: Point3<S>,
     pub radius: S,
 }
+
+impl<S: BaseFloat> Sphere<S> {
+    /// Construct a new sphere from its center and radius.
+    #[inline]
+    pub fn new(center: Point3<S>, radius: S) -> Sphere<S> {
+        Sphere { center: center, radius: radius }
+    }
+
+    /// Tests whether a point lies inside or on the surface of the sphere.
+    #[inline]
+    pub fn contains(&self, p: &Point3<S>) -> bool {
+        p.sub_p(&self.center).length2() <= self.radius * self.radius
+    }
+
+    /// Fits a sphere to a set of points using Ritter's method. This is fast,
+    /// but the sphere is usually a little larger than the smallest one that
+    /// contains the points. Returns `None` if there are no points.
+    ///
+    /// An initial sphere is built around the two points furthest apart along
+    /// a guessed direction, and is then grown just enough to take in each
+    /// point that lies outside of it (see _An Efficient Bounding Sphere_, Jack
+    /// Ritter, Graphics Gems, 1990).
+    pub fn from_points_ritter(points: &[Point3<S>]) -> Option<Sphere<S>> {
+        if points.is_empty() { return None; }
+
+        let furthest = |from: &Point3<S>| {
+            points.iter().fold(*from, |best, p| {
+                if p.sub_p(from).length2() > best.sub_p(from).length2() { *p } else { best }
+            })
+        };
+        let a = furthest(&points[0]);
+        let b = furthest(&a);
+
+        let two = one::<S>() + one::<S>();
+        let mut center = a.add_v(&b.sub_p(&a).div_s(two));
+        let mut radius = b.sub_p(&a).length() / two;
+        for p in points.iter() {
+            let d = p.sub_p(&center);
+            let dist = d.length();
+            if dist > radius {
+                let new_radius = (radius + dist) / two;
+                center = center.add_v(&d.mul_s((new_radius - radius) / dist));
+                radius = new_radius;
+            }
+        }
+        Some(Sphere::new(center, radius))
+    }
+
+    /// Finds the smallest sphere that contains a set of points, using Welzl's
+    /// algorithm with the move-to-front heuristic. Returns `None` if there are
+    /// no points.
+    ///
+    /// The sphere is determined by at most four points on its surface. These
+    /// are found by adding the points one at a time, and recomputing the
+    /// sphere with the new point on its surface whenever a point lies outside
+    /// of it (see _Smallest enclosing disks (balls and ellipsoids)_, Emo Welzl,
+    /// 1991, and _Fast and Robust Smallest Enclosing Balls_, Bernd Gärtner,
+    /// 1999). The expected running time is linear if the points are in random
+    /// order.
+    pub fn from_points_welzl(points: &[Point3<S>]) -> Option<Sphere<S>> {
+        let mut points = Vec::from_slice(points);
+        let end = points.len();
+        move_to_front(&mut points, end, &mut Vec::new())
+    }
+}
+
+/// Finds the smallest sphere containing the first `end` points, with the
+/// `boundary` points on its surface. Points that are found to lie outside of
+/// the sphere are moved to the front of the list, so that later calls will
+/// consider them first.
+fn move_to_front<S: BaseFloat>(points: &mut Vec<Point3<S>>, end: uint,
+                               boundary: &mut Vec<Point3<S>>) -> Option<Sphere<S>> {
+    let mut sphere = circumsphere(boundary.as_slice());
+    if boundary.len() == 4 { return sphere; }
+
+    for i in range(0, end) {
+        let p = points[i];
+        let inside = match sphere {
+            Some(ref s) => {
+                // allow for rounding errors in the spheres built from the
+                // boundary points
+                let tolerance = epsilon::<S>() * if s.radius > one() { s.radius } else { one() };
+                p.sub_p(&s.center).length() <= s.radius + tolerance
+            }
+            None => false,
+        };
+        if !inside {
+            boundary.push(p);
+            sphere = move_to_front(points, i, boundary);
+            boundary.pop();
+            for k in range(0, i).rev() {
+                points.as_mut_slice().swap(k, k + 1);
+            }
+        }
+    }
+    sphere
+}
+
+/// The smallest sphere with up to four points on its surface.
+fn circumsphere<S: BaseFloat>(points: &[Point3<S>]) -> Option<Sphere<S>> {
+    let two = one::<S>() + one::<S>();
+    match points.len() {
+        0 => None,
+        1 => Some(Sphere::new(points[0], zero())),
+        2 => {
+            let (a, b) = (points[0], points[1]);
+            Some(Sphere::new(a.add_v(&b.sub_p(&a).div_s(two)), b.sub_p(&a).length() / two))
+        }
+        3 => {
+            let (a, b, c) = (points[0], points[1], points[2]);
+            let (ab, ac) = (b.sub_p(&a), c.sub_p(&a));
+            let n = ab.cross(&ac);
+            let denom = two * n.length2();
+            if denom == zero() {
+                // the points are collinear, so the sphere is determined by
+                // the two that are furthest apart
+                return largest(&[circumsphere(&[a, b]), circumsphere(&[a, c]), circumsphere(&[b, c])]);
+            }
+            let offset = n.cross(&ab).mul_s(ac.length2()).add_v(&ac.cross(&n).mul_s(ab.length2())).div_s(denom);
+            Some(Sphere::new(a.add_v(&offset), offset.length()))
+        }
+        _ => {
+            let (a, b, c, d) = (points[0], points[1], points[2], points[3]);
+            let (ab, ac, ad) = (b.sub_p(&a), c.sub_p(&a), d.sub_p(&a));
+            let denom = two * ab.dot(&ac.cross(&ad));
+            if denom == zero() {
+                // the points are coplanar, so the sphere is determined by
+                // three of them
+                return largest(&[circumsphere(&[a, b, c]), circumsphere(&[a, b, d]),
+                                 circumsphere(&[a, c, d]), circumsphere(&[b, c, d])]);
+            }
+            let offset = ac.cross(&ad).mul_s(ab.length2())
+                .add_v(&ad.cross(&ab).mul_s(ac.length2()))
+                .add_v(&ab.cross(&ac).mul_s(ad.length2()))
+                .div_s(denom);
+            Some(Sphere::new(a.add_v(&offset), offset.length()))
+        }
+    }
+}
+
+fn largest<S: BaseFloat>(spheres: &[Option<Sphere<S>>]) -> Option<Sphere<S>> {
+    spheres.iter().fold(None, |best: Option<Sphere<S>>, s| match (best, *s) {
+        (Some(b), Some(s)) => Some(if s.radius > b.radius { s } else { b }),
+        (None, s) => s,
+        (b, None) => b,
+    })
+}
+
+impl<S: BaseFloat> Sphere<S> {
+    /// Sweeps the sphere along `motion`, and finds the time at which it first
+    /// touches a plane, as a fraction of `motion` between `0` and `1`. This is
+    /// returned along with the unit normal of the plane on the side that the
+    /// sphere approaches from. If the sphere already touches the plane, the
+    /// time is zero.
+    pub fn sweep_plane(&self, motion: &Vector3<S>, plane: &Plane<S>) -> Option<(S, Vector3<S>)> {
+        let len = plane.n.length();
+        let dist = plane.distance(&self.center) / len;
+        let n = if dist < zero() { plane.n.div_s(-len) } else { plane.n.div_s(len) };
+        let dist = abs(dist);
+        if dist <= self.radius { return Some((zero(), n)); }
+
+        let speed = -n.dot(motion);
+        if speed <= zero() { return None; }
+        let t = (dist - self.radius) / speed;
+        if t <= one() { Some((t, n)) } else { None }
+    }
+
+    /// Sweeps the sphere along `motion`, and finds the time at which it first
+    /// touches another, stationary, sphere, as a fraction of `motion` between
+    /// `0` and `1`. This is returned along with the unit normal of the other
+    /// sphere at the point of contact. If both spheres are moving, `motion`
+    /// should be the motion of this sphere relative to the other one.
+    pub fn sweep_sphere(&self, motion: &Vector3<S>, other: &Sphere<S>) -> Option<(S, Vector3<S>)> {
+        let r = self.radius + other.radius;
+        let s = self.center.sub_p(&other.center);
+        let c = s.length2() - r * r;
+        if c <= zero() { return Some((zero(), contact_normal(&s, motion))); }
+
+        let a = motion.length2();
+        let b = s.dot(motion);
+        if a == zero() || b >= zero() { return None; }
+        let discr = b * b - a * c;
+        if discr < zero() { return None; }
+        let t = (-b - discr.sqrt()) / a;
+        if t <= one() { Some((t, s.add_v(&motion.mul_s(t)).normalize())) } else { None }
+    }
+
+    /// Sweeps the sphere along `motion`, and finds the time at which it first
+    /// touches a box, as a fraction of `motion` between `0` and `1`. This is
+    /// returned along with the unit normal of the surface of the box at the
+    /// point of contact, which is an edge or a corner of the box if the
+    /// sphere hits one. If the sphere already touches the box, the time is
+    /// zero.
+    pub fn sweep_aabb(&self, motion: &Vector3<S>, aabb: &Aabb3<S>) -> Option<(S, Vector3<S>)> {
+        let r = self.radius;
+        if aabb.distance2(&self.center) <= r * r {
+            let d = self.center.sub_p(&aabb.closest_point(&self.center));
+            let n = if d.length2() > zero() { d.normalize() } else { aabb.normal_at(&self.center) };
+            return Some((zero(), n));
+        }
+        if motion.length2() == zero() { return None; }
+
+        // the center of the sphere touches the box rounded by the radius of
+        // the sphere, which is the union of the box grown along each of its
+        // axes, and the capsules around its edges
+        let ray = Ray::new(self.center, *motion);
+        let mut t = None;
+        for i in range(0u, 3) {
+            let mut grow = Vector3::zero();
+            grow[i] = r;
+            let slab = Aabb3::new(aabb.min.add_v(&-grow), aabb.max.add_v(&grow));
+            t = earliest(t, (ray, slab).intersection());
+        }
+        let corner = |i: uint| {
+            Point3::new(if i & 1 == 0 { aabb.min.x } else { aabb.max.x },
+                        if i & 2 == 0 { aabb.min.y } else { aabb.max.y },
+                        if i & 4 == 0 { aabb.min.z } else { aabb.max.z })
+        };
+        for i in range(0u, 8) {
+            for k in range(0u, 3) {
+                if i & (1 << k) == 0 {
+                    let edge = Capsule::from_points(corner(i), corner(i | (1 << k)), r);
+                    t = earliest(t, capsule_entry(&edge, &ray));
+                }
+            }
+        }
+        self.sweep_contact(motion, t, |p| aabb.closest_point(p))
+    }
+
+    /// Sweeps the sphere along `motion`, and finds the time at which it first
+    /// touches a triangle, as a fraction of `motion` between `0` and `1`. This
+    /// is returned along with the unit normal of the surface of the triangle
+    /// at the point of contact, which is an edge or a corner of the triangle
+    /// if the sphere hits one. If the sphere already touches the triangle,
+    /// the time is zero.
+    pub fn sweep_triangle(&self, motion: &Vector3<S>, tri: &Triangle3<S>) -> Option<(S, Vector3<S>)> {
+        let r = self.radius;
+        let n = tri.normal();
+        let d = self.center.sub_p(&tri.closest_point(&self.center));
+        if d.length2() <= r * r {
+            // if the center lies on the triangle, face away from the motion
+            let away = if n.dot(motion) > zero() { -n } else { n };
+            return Some((zero(), contact_normal(&d, &-away)));
+        }
+        if motion.length2() == zero() { return None; }
+
+        // the face of the triangle, on the side that the sphere starts on
+        let mut t = None;
+        let dist = n.dot(&self.center.sub_p(&tri.a));
+        let n = if dist < zero() { -n } else { n };
+        let speed = -n.dot(motion);
+        if abs(dist) > r && speed > zero() {
+            let t_face = (abs(dist) - r) / speed;
+            let p = self.center.add_v(&motion.mul_s(t_face)).add_v(&n.mul_s(-r));
+            let (u, v, w) = tri.barycentric(&p);
+            if u >= zero() && v >= zero() && w >= zero() { t = Some(t_face); }
+        }
+
+        // the edges and corners of the triangle
+        let ray = Ray::new(self.center, *motion);
+        for &(a, b) in [(tri.a, tri.b), (tri.b, tri.c), (tri.c, tri.a)].iter() {
+            t = earliest(t, capsule_entry(&Capsule::from_points(a, b, r), &ray));
+        }
+        self.sweep_contact(motion, t, |p| tri.closest_point(p))
+    }
+
+    /// Finishes a sweep test, given the time at which the center of the
+    /// sphere reaches the surface of the swept shape, and a function finding
+    /// the closest point of the shape to a point.
+    fn sweep_contact(&self, motion: &Vector3<S>, t: Option<S>,
+                     closest: |&Point3<S>| -> Point3<S>) -> Option<(S, Vector3<S>)> {
+        match t {
+            Some(t) if t <= one() => {
+                let p = self.center.add_v(&motion.mul_s(t));
+                Some((t, p.sub_p(&closest(&p)).normalize()))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes the vector from a shape to the center of a sphere touching it,
+/// or if that is zero, returns the direction opposite to the motion of the
+/// sphere.
+fn contact_normal<S: BaseFloat>(d: &Vector3<S>, motion: &Vector3<S>) -> Vector3<S> {
+    if d.length2() > zero() { d.normalize() }
+    else if motion.length2() > zero() { -motion.normalize() }
+    else { Vector3::unit_z() }
+}
+
+/// The parameter at which a ray from outside of a capsule enters it.
+fn capsule_entry<S: BaseFloat>(capsule: &Capsule<S>, ray: &Ray3<S>) -> Option<S> {
+    match (*capsule, *ray).intersection() {
+        Some((t0, _)) if t0 >= zero() => Some(t0),
+        _ => None,
+    }
+}
+
+fn earliest<S: BaseFloat>(a: Option<S>, b: Option<S>) -> Option<S> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+impl<S: BaseFloat + 'static> Sphere<S> {
+    /// Transforms the sphere by an affine transformation matrix. The radius is
+    /// scaled by the largest factor by which the transformation stretches any
+    /// direction, so the result contains the transformed sphere, and is exact
+    /// if the scaling is uniform.
+    pub fn transform_matrix4(&self, m: &Matrix4<S>) -> Sphere<S> {
+        let center = Point3::from_homogeneous(&m.mul_v(&self.center.to_homogeneous()));
+        let linear = Matrix3::from_cols(m.x.truncate(), m.y.truncate(), m.z.truncate());
+        // the largest singular value of the matrix, which is the square root
+        // of the largest eigenvalue of `linear^T * linear`
+        let (values, _) = linear.transpose().mul_m(&linear).symmetric_eigen();
+        let scale = values.x.max(zero()).sqrt();
+        Sphere::new(center, self.radius * scale)
+    }
+
+    /// Transforms the sphere, returning a sphere that contains the result.
+    #[inline]
+    pub fn transform<T: Transform3<S>>(&self, t: &T) -> Sphere<S> {
+        self.transform_matrix4(&t.to_matrix4())
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support mappings of convex shapes
+
+use std::num::zero;
+
+use aabb::Aabb3;
+use capsule::Capsule;
+use cylinder::Cylinder;
+use num::BaseFloat;
+use obb::Obb3;
+use point::{Point, Point3};
+use sphere::Sphere;
+use triangle::Triangle3;
+use vector::{Vector, Vector3, EuclideanVector};
+
+/// A convex shape that is described by its support mapping.
+///
+/// The support point of a shape in a given direction is the point of the
+/// shape that lies furthest along that direction. This is all that the
+/// distance and penetration queries in the `gjk` module need to know about a
+/// shape, so they work for any pair of shapes that implement this trait.
+pub trait SupportFunction<S: BaseFloat> {
+    /// Returns a point of the shape that is furthest along `direction`. The
+    /// direction does not need to be of unit length. This may fail if the
+    /// shape is empty.
+    fn support(&self, direction: &Vector3<S>) -> Point3<S>;
+
+    /// Returns `false` if the shape contains no points, in which case it has
+    /// no support points. Only point sets can be empty, so this defaults to
+    /// `true`.
+    fn has_support(&self) -> bool { true }
+}
+
+/// Returns the point of a point set that is furthest along `direction`. Fails
+/// if the set is empty.
+fn furthest<'a, S: BaseFloat, I: Iterator<&'a Point3<S>>>(mut points: I, direction: &Vector3<S>) -> Point3<S> {
+    let first = *points.next().expect("cannot find the support point of an empty point set");
+    points.fold(first, |best, p| {
+        if p.dot(direction) > best.dot(direction) { *p } else { best }
+    })
+}
+
+impl<S: BaseFloat> SupportFunction<S> for Sphere<S> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        if direction.length2() == zero() { return self.center; }
+        self.center.add_v(&direction.normalize_to(self.radius))
+    }
+}
+
+impl<S: BaseFloat> SupportFunction<S> for Aabb3<S> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        Point3::new(if direction.x < zero() { self.min.x } else { self.max.x },
+                    if direction.y < zero() { self.min.y } else { self.max.y },
+                    if direction.z < zero() { self.min.z } else { self.max.z })
+    }
+}
+
+impl<S: BaseFloat + 'static> SupportFunction<S> for Obb3<S> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        let axes = self.axes();
+        let mut p = self.center;
+        for i in range(0u, 3) {
+            let e = self.extents[i];
+            p.add_self_v(&axes[i].mul_s(if direction.dot(&axes[i]) < zero() { -e } else { e }));
+        }
+        p
+    }
+}
+
+impl<S: BaseFloat> SupportFunction<S> for Capsule<S> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        let end = if direction.dot(&self.axis) < zero() { -self.axis } else { self.axis };
+        Sphere::new(self.center.add_v(&end), self.radius).support(direction)
+    }
+}
+
+impl<S: BaseFloat> SupportFunction<S> for Cylinder<S> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        let len2 = self.axis.length2();
+        if len2 == zero() {
+            return Sphere::new(self.center, self.radius).support(direction);
+        }
+        let h = direction.dot(&self.axis);
+        let end = if h < zero() { -self.axis } else { self.axis };
+        // the furthest point on the rim of the cap
+        let radial = direction.sub_v(&self.axis.mul_s(h / len2));
+        let p = self.center.add_v(&end);
+        if radial.length2() == zero() { p }
+        else { p.add_v(&radial.normalize_to(self.radius)) }
+    }
+}
+
+impl<S: BaseFloat> SupportFunction<S> for Triangle3<S> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        furthest([self.a, self.b, self.c].iter(), direction)
+    }
+}
+
+/// The convex hull of a set of points. `support` fails if the set is empty,
+/// which can be checked with `has_support`.
+impl<'a, S: BaseFloat> SupportFunction<S> for &'a [Point3<S>] {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        furthest(self.iter(), direction)
+    }
+
+    fn has_support(&self) -> bool { self.len() > 0 }
+}
+
+/// The convex hull of a set of points. `support` fails if the set is empty,
+/// which can be checked with `has_support`.
+impl<S: BaseFloat> SupportFunction<S> for Vec<Point3<S>> {
+    fn support(&self, direction: &Vector3<S>) -> Point3<S> {
+        furthest(self.iter(), direction)
+    }
+
+    fn has_support(&self) -> bool { self.len() > 0 }
+}
@@ -16,12 +16,13 @@
 use std::{fmt, num};
 
 use approx::ApproxEq;
-use matrix::{Matrix, Matrix4, ToMatrix4};
+use matrix::{Matrix, Matrix3, Matrix4, ToMatrix4};
 use num::{BaseNum, BaseFloat};
 use point::{Point, Point3};
+use quaternion::{Quaternion, ToQuaternion};
 use ray::Ray;
 use rotation::{Rotation, Rotation3};
-use vector::{Vector, Vector3};
+use vector::{Vector, Vector3, EuclideanVector};
 
 /// A trait representing an [affine
 /// transformation](https://en.wikipedia.org/wiki/Affine_transformation) that
@@ -151,6 +152,87 @@ impl<S: BaseFloat + 'static, R: Rotation3<S>> ToMatrix4<S> for Decomposed<S, Vec
 
 impl<S: BaseFloat, R: Rotation3<S>> Transform3<S> for Decomposed<S,Vector3<S>,R> {}
 
+/// The reasons that a matrix cannot be decomposed into a scale, rotation and
+/// translation.
+#[deriving(Clone, PartialEq)]
+pub enum DecomposeError<S> {
+    /// The bottom row of the matrix is not `[0, 0, 0, w]`, so it performs a
+    /// perspective projection.
+    PerspectiveMatrix,
+    /// The matrix flattens space onto a plane, a line or a point.
+    SingularMatrix,
+    /// The matrix does not preserve right angles.
+    ShearedMatrix,
+    /// The matrix scales its axes by different amounts, which are given in
+    /// local space. The last one is negative if the matrix contains a
+    /// reflection. These matrices can be decomposed into a
+    /// `NonUniformDecomposed` instead.
+    NonUniformScale(Vector3<S>),
+}
+
+impl<S: BaseFloat + 'static> Decomposed<S, Vector3<S>, Quaternion<S>> {
+    /// Decomposes an affine transformation matrix into a uniform scale, a
+    /// rotation and a translation. A matrix that contains a reflection is
+    /// given a negative scale.
+    ///
+    /// The matrix may have a bottom row of `[0, 0, 0, w]` for any non-zero
+    /// `w`, in which case it is divided by `w` first. Matrices that cannot be
+    /// represented exactly, within the default epsilon, return an error.
+    pub fn from_matrix4(m: &Matrix4<S>) -> Result<Decomposed<S, Vector3<S>, Quaternion<S>>, DecomposeError<S>> {
+        let (scale, axes, disp) = try!(decompose_affine(m));
+        let max = scale.x.abs().max(scale.y.abs()).max(scale.z.abs());
+        if !(scale.x / max).approx_eq(&(scale.y / max)) ||
+           !(scale.x / max).approx_eq(&(scale.z.abs() / max)) {
+            return Err(NonUniformScale(scale));
+        }
+
+        // a reflection is represented by negating the scale, which is a
+        // reflection through the origin, and rotating by a half turn
+        let s = (scale.x + scale.y + scale.z.abs()) / num::cast(3i).unwrap();
+        let (s, rot) = if scale.z < num::zero() {
+            (-s, Matrix3::from_cols(-axes.x, -axes.y, axes.z))
+        } else {
+            (s, axes)
+        };
+        Ok(Decomposed {
+            scale: s,
+            rot: rot.to_quaternion().normalize(),
+            disp: disp,
+        })
+    }
+}
+
+/// Splits an affine transformation matrix into a scale along each axis, a
+/// rotation matrix and a translation, after dividing it by its bottom-right
+/// element. If the matrix contains a reflection, the last scale is negative.
+fn decompose_affine<S: BaseFloat + 'static>(m: &Matrix4<S>) -> Result<(Vector3<S>, Matrix3<S>, Vector3<S>), DecomposeError<S>> {
+    if !Vector3::new(m.x.w, m.y.w, m.z.w).approx_eq(&Vector3::zero()) || m.w.w.approx_eq(&num::zero()) {
+        return Err(PerspectiveMatrix);
+    }
+    let m = m.mul_s(num::one::<S>() / m.w.w);
+    let linear = Matrix3::from_cols(m.x.truncate(), m.y.truncate(), m.z.truncate());
+
+    let scale = Vector3::new(linear.x.length(), linear.y.length(), linear.z.length());
+    let max = scale.comp_max();
+    let det = linear.determinant();
+    if max == num::zero() || (det / (max * max * max)).approx_eq(&num::zero()) {
+        return Err(SingularMatrix);
+    }
+
+    let axes = Matrix3::from_cols(linear.x.div_s(scale.x), linear.y.div_s(scale.y), linear.z.div_s(scale.z));
+    if !axes.x.dot(&axes.y).approx_eq(&num::zero()) ||
+       !axes.y.dot(&axes.z).approx_eq(&num::zero()) ||
+       !axes.z.dot(&axes.x).approx_eq(&num::zero()) {
+        return Err(ShearedMatrix);
+    }
+
+    if det < num::zero() {
+        Ok((Vector3::new(scale.x, scale.y, -scale.z), Matrix3::from_cols(axes.x, axes.y, -axes.z), m.w.truncate()))
+    } else {
+        Ok((scale, axes, m.w.truncate()))
+    }
+}
+
 impl<S: BaseFloat, R: fmt::Show + Rotation3<S>> fmt::Show for Decomposed<S,Vector3<S>,R> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "(scale({}), rot({}), disp{})",
@@ -158,6 +240,132 @@ impl<S: BaseFloat, R: fmt::Show + Rotation3<S>> fmt::Show for Decomposed<S,Vecto
     }
 }
 
+/// A transformation consisting of a separate scale along each axis, a
+/// rotation and a displacement vector, which are applied in that order.
+///
+/// Unlike `Decomposed`, these transformations cannot always be combined or
+/// inverted exactly. When the scale is not uniform, rotating the scaled axes
+/// and then scaling them again shears space, which cannot be represented by a
+/// scale and a rotation. `concat` and `invert` give the closest
+/// transformation in that case, as described below. Convert the
+/// transformations to matrices to combine them exactly.
+#[deriving(Clone, PartialEq, Encodable, Decodable)]
+pub struct NonUniformDecomposed<V, R> {
+    pub scale: V,
+    pub rot: R,
+    pub disp: V,
+}
+
+impl<S: BaseFloat + 'static> NonUniformDecomposed<Vector3<S>, Quaternion<S>> {
+    /// Decomposes an affine transformation matrix into a scale along each
+    /// axis, a rotation and a translation. A matrix that contains a
+    /// reflection is given a negative scale along the local `z` axis.
+    ///
+    /// The matrix may have a bottom row of `[0, 0, 0, w]` for any non-zero
+    /// `w`, in which case it is divided by `w` first. Matrices that cannot be
+    /// represented exactly, within the default epsilon, return an error.
+    pub fn from_matrix4(m: &Matrix4<S>) -> Result<NonUniformDecomposed<Vector3<S>, Quaternion<S>>, DecomposeError<S>> {
+        let (scale, axes, disp) = try!(decompose_affine(m));
+        Ok(NonUniformDecomposed {
+            scale: scale,
+            rot: axes.to_quaternion().normalize(),
+            disp: disp,
+        })
+    }
+}
+
+impl<S: BaseFloat + 'static, R: Rotation3<S>> Transform<S, Vector3<S>, Point3<S>> for NonUniformDecomposed<Vector3<S>, R> {
+    #[inline]
+    fn identity() -> NonUniformDecomposed<Vector3<S>, R> {
+        NonUniformDecomposed {
+            scale: Vector3::from_value(num::one()),
+            rot: Rotation::identity(),
+            disp: Vector3::zero(),
+        }
+    }
+
+    #[inline]
+    fn look_at(eye: &Point3<S>, center: &Point3<S>, up: &Vector3<S>) -> NonUniformDecomposed<Vector3<S>, R> {
+        let origin: Point3<S> = Point::origin();
+        let rot: R = Rotation::look_at(&center.sub_p(eye), up);
+        let disp = rot.rotate_vector(&origin.sub_p(eye));
+        NonUniformDecomposed {
+            scale: Vector3::from_value(num::one()),
+            rot: rot,
+            disp: disp,
+        }
+    }
+
+    #[inline]
+    fn transform_vector(&self, vec: &Vector3<S>) -> Vector3<S> {
+        self.rot.rotate_vector(&vec.mul_v(&self.scale))
+    }
+
+    #[inline]
+    fn transform_point(&self, point: &Point3<S>) -> Point3<S> {
+        self.rot.rotate_point(&Point::from_vec(&point.to_vec().mul_v(&self.scale))).add_v(&self.disp)
+    }
+
+    /// Combines the transformations. The result is exact if the scale of
+    /// `self` is uniform, or if the rotation of `other` maps each axis onto
+    /// an axis. Otherwise, the scale of `self` becomes a stretch along skewed
+    /// axes after the rotation of `other`, and only the part of it along the
+    /// axes of the result is kept, dropping the shear.
+    fn concat(&self, other: &NonUniformDecomposed<Vector3<S>, R>) -> NonUniformDecomposed<Vector3<S>, R> {
+        let r = other.rot.to_matrix3();
+        let s = self.scale;
+        let stretch = |c: Vector3<S>| c.x * c.x * s.x + c.y * c.y * s.y + c.z * c.z * s.z;
+        NonUniformDecomposed {
+            scale: Vector3::new(stretch(r.x), stretch(r.y), stretch(r.z)).mul_v(&other.scale),
+            rot: self.rot.concat(&other.rot),
+            disp: self.transform_as_point(&other.disp),
+        }
+    }
+
+    /// Creates the inverse transformation, or returns `None` if the scale is
+    /// zero along any axis. The result is exact if the scale is uniform, or
+    /// if the rotation maps each axis onto an axis. Otherwise, the inverse
+    /// scale becomes a stretch along skewed axes, and only the part of it
+    /// along the axes of the result is kept, dropping the shear. The result
+    /// always maps the origin to the same point as the exact inverse.
+    fn invert(&self) -> Option<NonUniformDecomposed<Vector3<S>, R>> {
+        if self.scale.x.approx_eq(&num::zero()) ||
+           self.scale.y.approx_eq(&num::zero()) ||
+           self.scale.z.approx_eq(&num::zero()) {
+            return None;
+        }
+
+        let inv_scale = Vector3::from_value(num::one()).div_v(&self.scale);
+        let r = self.rot.to_matrix3();
+        let s = inv_scale;
+        let stretch = |i: uint| r.x[i] * r.x[i] * s.x + r.y[i] * r.y[i] * s.y + r.z[i] * r.z[i] * s.z;
+        let rot = self.rot.invert();
+        let disp = -rot.rotate_vector(&self.disp).mul_v(&inv_scale);
+        Some(NonUniformDecomposed {
+            scale: Vector3::new(stretch(0), stretch(1), stretch(2)),
+            rot: rot,
+            disp: disp,
+        })
+    }
+}
+
+impl<S: BaseFloat + 'static, R: Rotation3<S>> ToMatrix4<S> for NonUniformDecomposed<Vector3<S>, R> {
+    fn to_matrix4(&self) -> Matrix4<S> {
+        let mut m = self.rot.to_matrix3().mul_m(&Matrix3::from_diagonal(&self.scale)).to_matrix4();
+        m.w = self.disp.extend(num::one());
+        m
+    }
+}
+
+impl<S: BaseFloat + 'static, R: Rotation3<S>> Transform3<S> for NonUniformDecomposed<Vector3<S>, R> {}
+
+impl<S: BaseFloat, R: fmt::Show + Rotation3<S>> fmt::Show for NonUniformDecomposed<Vector3<S>, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(scale{}, rot({}), disp{})",
+            self.scale, self.rot, self.disp)
+    }
+}
+
 /// A homogeneous transformation matrix.
 #[deriving(Encodable, Decodable)]
 pub struct AffineMatrix3<S> {
@@ -196,6 +404,15 @@ impl<S: BaseFloat + 'static> Transform<S, Vector3<S>, Point3<S>> for AffineMatri
     }
 }
 
+impl<S: BaseFloat + 'static> AffineMatrix3<S> {
+    /// Decomposes the transformation into a uniform scale, a rotation and a
+    /// translation. See `Decomposed::from_matrix4` for details.
+    #[inline]
+    pub fn decompose(&self) -> Result<Decomposed<S, Vector3<S>, Quaternion<S>>, DecomposeError<S>> {
+        Decomposed::from_matrix4(&self.mat)
+    }
+}
+
 impl<S: BaseNum> ToMatrix4<S> for AffineMatrix3<S> {
     #[inline] fn to_matrix4(&self) -> Matrix4<S> { self.mat.clone() }
 }
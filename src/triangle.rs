@@ -0,0 +1,252 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Triangles
+
+use std::num::{zero, one, cast, abs};
+
+use approx::ApproxEq;
+use num::BaseFloat;
+use plane::Plane;
+use point::{Point, Point2, Point3};
+use ray::Ray3;
+use vector::{Vector, Vector3, EuclideanVector};
+
+/// A two-dimensional triangle.
+#[deriving(Clone, PartialEq, Encodable, Decodable)]
+pub struct Triangle2<S> {
+    pub a: Point2<S>,
+    pub b: Point2<S>,
+    pub c: Point2<S>,
+}
+
+impl<S: BaseFloat> Triangle2<S> {
+    /// Construct a new triangle from its three corners.
+    #[inline]
+    pub fn new(a: Point2<S>, b: Point2<S>, c: Point2<S>) -> Triangle2<S> {
+        Triangle2 { a: a, b: b, c: c }
+    }
+
+    /// The signed area of the triangle. This is positive if the corners are in
+    /// counter-clockwise order, and negative if they are clockwise.
+    #[inline]
+    pub fn signed_area(&self) -> S {
+        let two = one::<S>() + one::<S>();
+        self.b.sub_p(&self.a).perp_dot(&self.c.sub_p(&self.a)) / two
+    }
+
+    /// The area of the triangle.
+    #[inline]
+    pub fn area(&self) -> S {
+        abs(self.signed_area())
+    }
+
+    /// The center of mass of the triangle.
+    #[inline]
+    pub fn centroid(&self) -> Point2<S> {
+        centroid(&self.a, &self.b, &self.c)
+    }
+
+    /// Returns the barycentric coordinates `(u, v, w)` of `p`, such that
+    /// `p = u*a + v*b + w*c`. The point is inside the triangle if all of the
+    /// coordinates are between `0` and `1`.
+    #[inline]
+    pub fn barycentric(&self, p: &Point2<S>) -> (S, S, S) {
+        barycentric(&self.a, &self.b, &self.c, p)
+    }
+
+    /// Tests whether a point lies inside or on the edges of the triangle.
+    #[inline]
+    pub fn contains(&self, p: &Point2<S>) -> bool {
+        let (u, v, w) = self.barycentric(p);
+        u >= zero() && v >= zero() && w >= zero()
+    }
+
+    /// Returns the point inside or on the edges of the triangle that is
+    /// closest to `p`.
+    #[inline]
+    pub fn closest_point(&self, p: &Point2<S>) -> Point2<S> {
+        closest_point(&self.a, &self.b, &self.c, p)
+    }
+}
+
+/// A three-dimensional triangle.
+#[deriving(Clone, PartialEq, Encodable, Decodable)]
+pub struct Triangle3<S> {
+    pub a: Point3<S>,
+    pub b: Point3<S>,
+    pub c: Point3<S>,
+}
+
+impl<S: BaseFloat> Triangle3<S> {
+    /// Construct a new triangle from its three corners.
+    #[inline]
+    pub fn new(a: Point3<S>, b: Point3<S>, c: Point3<S>) -> Triangle3<S> {
+        Triangle3 { a: a, b: b, c: c }
+    }
+
+    /// The unit normal of the triangle. This faces towards the side from
+    /// which the corners appear in counter-clockwise order.
+    #[inline]
+    pub fn normal(&self) -> Vector3<S> {
+        self.b.sub_p(&self.a).cross(&self.c.sub_p(&self.a)).normalize()
+    }
+
+    /// The plane containing the triangle, with the same normal. Returns `None`
+    /// if the triangle is degenerate.
+    #[inline]
+    pub fn to_plane(&self) -> Option<Plane<S>> {
+        Plane::from_points(self.a, self.b, self.c)
+    }
+
+    /// The area of the triangle.
+    #[inline]
+    pub fn area(&self) -> S {
+        let two = one::<S>() + one::<S>();
+        self.b.sub_p(&self.a).cross(&self.c.sub_p(&self.a)).length() / two
+    }
+
+    /// The center of mass of the triangle.
+    #[inline]
+    pub fn centroid(&self) -> Point3<S> {
+        centroid(&self.a, &self.b, &self.c)
+    }
+
+    /// Returns the barycentric coordinates `(u, v, w)` of `p`, such that
+    /// `p = u*a + v*b + w*c`. If `p` does not lie in the plane of the
+    /// triangle, these are the coordinates of its projection onto the plane.
+    #[inline]
+    pub fn barycentric(&self, p: &Point3<S>) -> (S, S, S) {
+        barycentric(&self.a, &self.b, &self.c, p)
+    }
+
+    /// Returns the point inside or on the edges of the triangle that is
+    /// closest to `p`.
+    #[inline]
+    pub fn closest_point(&self, p: &Point3<S>) -> Point3<S> {
+        closest_point(&self.a, &self.b, &self.c, p)
+    }
+
+    /// Finds the intersection of a ray with the triangle using the
+    /// Möller–Trumbore algorithm. On a hit, this returns the ray parameter
+    /// `t`, along with the barycentric coordinates `(u, v)` of `b` and `c` at
+    /// the point of intersection.
+    ///
+    /// If `cull_backface` is `true`, rays that hit the triangle from behind, as
+    /// determined by `normal`, are ignored.
+    pub fn intersect_ray(&self, ray: &Ray3<S>, cull_backface: bool) -> Option<(S, S, S)> {
+        let e1 = self.b.sub_p(&self.a);
+        let e2 = self.c.sub_p(&self.a);
+        let pvec = ray.direction.cross(&e2);
+        let det = e1.dot(&pvec);
+
+        // a determinant of zero means the ray is parallel to the triangle,
+        // and a negative one that it approaches from behind
+        if cull_backface && det <= zero() { return None; }
+        if det == zero() { return None; }
+
+        let inv_det = one::<S>() / det;
+        let tvec = ray.origin.sub_p(&self.a);
+        let u = tvec.dot(&pvec) * inv_det;
+        if u < zero() || u > one() { return None; }
+
+        let qvec = tvec.cross(&e1);
+        let v = ray.direction.dot(&qvec) * inv_det;
+        if v < zero() || u + v > one() { return None; }
+
+        let t = e2.dot(&qvec) * inv_det;
+        if t < zero() { None } else { Some((t, u, v)) }
+    }
+}
+
+impl<S: BaseFloat> ApproxEq<S> for Triangle2<S> {
+    #[inline]
+    fn approx_eq_eps(&self, other: &Triangle2<S>, epsilon: &S) -> bool {
+        self.a.approx_eq_eps(&other.a, epsilon) &&
+        self.b.approx_eq_eps(&other.b, epsilon) &&
+        self.c.approx_eq_eps(&other.c, epsilon)
+    }
+}
+
+impl<S: BaseFloat> ApproxEq<S> for Triangle3<S> {
+    #[inline]
+    fn approx_eq_eps(&self, other: &Triangle3<S>, epsilon: &S) -> bool {
+        self.a.approx_eq_eps(&other.a, epsilon) &&
+        self.b.approx_eq_eps(&other.b, epsilon) &&
+        self.c.approx_eq_eps(&other.c, epsilon)
+    }
+}
+
+fn centroid<S: BaseFloat, V: Vector<S>, P: Point<S, V>>(a: &P, b: &P, c: &P) -> P {
+    let v = a.to_vec().add_v(&b.to_vec()).add_v(&c.to_vec());
+    Point::from_vec(&v.div_s(cast(3i).unwrap()))
+}
+
+fn barycentric<S: BaseFloat, V: Vector<S>, P: Point<S, V>>(a: &P, b: &P, c: &P, p: &P) -> (S, S, S) {
+    // see _Real Time Collision Detection_, p. 47
+    let (v0, v1, v2) = (b.sub_p(a), c.sub_p(a), p.sub_p(a));
+    let d00 = v0.dot(&v0);
+    let d01 = v0.dot(&v1);
+    let d11 = v1.dot(&v1);
+    let d20 = v2.dot(&v0);
+    let d21 = v2.dot(&v1);
+    let denom = d00 * d11 - d01 * d01;
+    let v = (d11 * d20 - d01 * d21) / denom;
+    let w = (d00 * d21 - d01 * d20) / denom;
+    (one::<S>() - v - w, v, w)
+}
+
+fn closest_point<S: BaseFloat, V: Vector<S>, P: Point<S, V>>(a: &P, b: &P, c: &P, p: &P) -> P {
+    // find the Voronoi region of the triangle that `p` lies in, and project
+    // it onto the corresponding feature (see _Real Time Collision Detection_,
+    // p. 141)
+    let ab = b.sub_p(a);
+    let ac = c.sub_p(a);
+
+    let ap = p.sub_p(a);
+    let d1 = ab.dot(&ap);
+    let d2 = ac.dot(&ap);
+    if d1 <= zero() && d2 <= zero() { return a.clone(); }
+
+    let bp = p.sub_p(b);
+    let d3 = ab.dot(&bp);
+    let d4 = ac.dot(&bp);
+    if d3 >= zero() && d4 <= d3 { return b.clone(); }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= zero() && d1 >= zero() && d3 <= zero() {
+        return a.add_v(&ab.mul_s(d1 / (d1 - d3)));
+    }
+
+    let cp = p.sub_p(c);
+    let d5 = ab.dot(&cp);
+    let d6 = ac.dot(&cp);
+    if d6 >= zero() && d5 <= d6 { return c.clone(); }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= zero() && d2 >= zero() && d6 <= zero() {
+        return a.add_v(&ac.mul_s(d2 / (d2 - d6)));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= zero() && d4 - d3 >= zero() && d5 - d6 >= zero() {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return b.add_v(&c.sub_p(b).mul_s(w));
+    }
+
+    // `p` projects onto the face of the triangle
+    let denom = one::<S>() / (va + vb + vc);
+    a.add_v(&ab.mul_s(vb * denom)).add_v(&ac.mul_s(vc * denom))
+}
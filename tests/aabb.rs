@@ -20,7 +20,7 @@ extern crate cgmath;
 use cgmath::{Aabb, Aabb2, Aabb3};
 use cgmath::{Point2, Point3};
 use cgmath::{Vector2, Vector3};
-use cgmath::{Ray, IntersectPoint};
+use cgmath::{Ray, Intersect, IntersectPoint};
 
 #[test]
 fn test_aabb() {
@@ -79,3 +79,118 @@ fn test_aabb_ray_intersect() {
     assert_eq!((ray3, aabb).intersection_point(), None);
     assert_eq!((ray4, aabb).intersection_point(), Some(Point2::new(5.0, 9.0)));
 }
+
+#[test]
+fn test_aabb3_ray_intersect() {
+    let aabb = Aabb3::new(Point3::new(-1.0f64, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+    let ray1 = Ray::new(Point3::new(0.0f64, 0.0, 5.0), Vector3::new(0.0, 0.0, -1.0));
+    let ray2 = Ray::new(Point3::new(5.0f64, 0.5, 0.0), Vector3::new(-1.0, 0.0, 0.0));
+    let ray3 = Ray::new(Point3::new(0.0f64, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+    let ray4 = Ray::new(Point3::new(5.0f64, 2.0, 0.0), Vector3::new(-1.0, 0.0, 0.0));
+    let ray5 = Ray::new(Point3::new(0.0f64, 0.0, 5.0), Vector3::new(0.0, 0.0, 1.0));
+
+    assert_eq!((ray1, aabb).intersection(), Some(4.0));
+    assert_eq!((ray1, aabb).intersection_point(), Some(Point3::new(0.0, 0.0, 1.0)));
+    assert_eq!((ray2, aabb).intersection_point(), Some(Point3::new(1.0, 0.5, 0.0)));
+    // starting inside, the ray hits the face it leaves through
+    assert_eq!((ray3, aabb).intersection_point(), Some(Point3::new(0.0, 1.0, 0.0)));
+    // parallel to the y slab, but outside of it
+    assert_eq!((ray4, aabb).intersection(), None);
+    // pointing away from the box
+    assert_eq!((ray5, aabb).intersection(), None);
+
+    assert_eq!(aabb.normal_at(&Point3::new(0.0, 0.0, 1.0)), Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(aabb.normal_at(&Point3::new(1.0, 0.5, 0.0)), Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(aabb.normal_at(&Point3::new(0.2, -1.0, 0.3)), Vector3::new(0.0, -1.0, 0.0));
+
+    assert_eq!(aabb.intersect_ray(&ray1), Some((4.0, Vector3::new(0.0, 0.0, 1.0))));
+    assert_eq!(aabb.intersect_ray(&ray3).map(|(_, n)| n), Some(Vector3::new(0.0, 1.0, 0.0)));
+    assert_eq!(aabb.intersect_ray(&ray5), None);
+    assert_eq!(aabb.intersect_ray(&Ray::new(Point3::new(0.0f64, 0.0, 0.0), Vector3::zero())), None);
+}
+
+#[test]
+fn test_ray3_flat_aabb_intersection() {
+    // a box with no thickness along z, hit from above and below
+    let flat = Aabb3::new(Point3::new(-1.0f64, -1.0, 0.0), Point3::new(1.0, 1.0, 0.0));
+    let down = Ray::new(Point3::new(0.5f64, 0.5, 2.0), Vector3::new(0.0, 0.0, -1.0));
+    let up = Ray::new(Point3::new(0.5f64, 0.5, -3.0), Vector3::new(0.0, 0.0, 1.0));
+    assert_eq!(flat.intersect_ray(&down), Some((2.0, Vector3::new(0.0, 0.0, 1.0))));
+    assert_eq!(flat.intersect_ray(&up), Some((3.0, Vector3::new(0.0, 0.0, -1.0))));
+    assert_eq!((down, flat).intersection(), Some(2.0));
+
+    let n = flat.normal_at(&Point3::new(0.5, 0.5, 0.0));
+    assert_eq!(n, Vector3::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn test_aabb_set_operations() {
+    let a = Aabb2::new(Point2::new(0i, 0i), Point2::new(4i, 4i));
+    let b = Aabb2::new(Point2::new(2i, 2i), Point2::new(6i, 6i));
+    let c = Aabb2::new(Point2::new(5i, 5i), Point2::new(6i, 6i));
+    let d = Aabb2::new(Point2::new(4i, 0i), Point2::new(5i, 1i));
+
+    assert_eq!(a.union(&b), Aabb2::new(Point2::new(0i, 0i), Point2::new(6i, 6i)));
+    assert_eq!(a.intersection(&b), Some(Aabb2::new(Point2::new(2i, 2i), Point2::new(4i, 4i))));
+    assert!(a.overlaps(&b));
+    assert!(!a.overlaps(&c));
+    assert_eq!(a.intersection(&c), None);
+    // boxes that touch on an edge overlap
+    assert!(a.overlaps(&d));
+
+    assert!(a.contains_aabb(&Aabb2::new(Point2::new(1i, 1i), Point2::new(4i, 4i))));
+    assert!(!a.contains_aabb(&b));
+    assert!(a.contains_inclusive(&Point2::new(4i, 4i)));
+    assert!(!a.contains(&Point2::new(4i, 4i)));
+
+    assert_eq!(a.closest_point(&Point2::new(6i, -2i)), Point2::new(4i, 0i));
+    assert_eq!(a.closest_point(&Point2::new(1i, 3i)), Point2::new(1i, 3i));
+    assert_eq!(a.distance2(&Point2::new(6i, -2i)), 8i);
+    assert_eq!(a.expand(1i), Aabb2::new(Point2::new(-1i, -1i), Point2::new(5i, 5i)));
+    assert_eq!(a.perimeter(), 16i);
+}
+
+#[test]
+fn test_aabb_construction() {
+    let points = [Point3::new(1.0f64, -2.0, 0.5), Point3::new(-1.0, 3.0, 0.0), Point3::new(0.0, 0.0, 2.0)];
+    let aabb: Option<Aabb3<f64>> = Aabb::from_points(points.iter().map(|&p| p));
+    assert_eq!(aabb, Some(Aabb3::new(Point3::new(-1.0, -2.0, 0.0), Point3::new(1.0, 3.0, 2.0))));
+    let empty: Option<Aabb3<f64>> = Aabb::from_points(points.iter().take(0).map(|&p| p));
+    assert_eq!(empty, None);
+
+    let aabb = Aabb3::new(Point3::new(0.0f64, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0));
+    assert_eq!(aabb.surface_area(), 22.0);
+    assert_eq!(aabb.distance(&Point3::new(0.5, 1.0, 5.0)), 2.0);
+    assert_eq!(aabb.distance(&Point3::new(0.5, 1.0, 1.0)), 0.0);
+}
+
+#[test]
+fn test_aabb_subdivision() {
+    let a = Aabb2::new(Point2::new(0i, 0i), Point2::new(4i, 4i));
+    let quadrants = a.quadrants();
+    assert_eq!(quadrants[0], Aabb2::new(Point2::new(0i, 0i), Point2::new(2i, 2i)));
+    assert_eq!(quadrants[1], Aabb2::new(Point2::new(2i, 0i), Point2::new(4i, 2i)));
+    assert_eq!(quadrants[3], Aabb2::new(Point2::new(2i, 2i), Point2::new(4i, 4i)));
+
+    let b = Aabb3::new(Point3::new(0i, 0i, 0i), Point3::new(2i, 2i, 2i));
+    let octants = b.octants();
+    assert_eq!(octants[0], Aabb3::new(Point3::new(0i, 0i, 0i), Point3::new(1i, 1i, 1i)));
+    assert_eq!(octants[5], Aabb3::new(Point3::new(1i, 0i, 1i), Point3::new(2i, 1i, 2i)));
+    assert_eq!(octants[7], Aabb3::new(Point3::new(1i, 1i, 1i), Point3::new(2i, 2i, 2i)));
+}
+
+#[test]
+fn test_aabb_sweep() {
+    let a = Aabb3::new(Point3::new(0f64, 0f64, 0f64), Point3::new(1f64, 1f64, 1f64));
+    let b = Aabb3::new(Point3::new(3f64, 0.5f64, 0.5f64), Point3::new(4f64, 2f64, 2f64));
+
+    let (t, n) = a.sweep_aabb(&Vector3::new(4f64, 0f64, 0f64), &b).unwrap();
+    assert_eq!(t, 0.5f64);
+    assert_eq!(n, Vector3::new(-1f64, 0f64, 0f64));
+    assert_eq!(a.sweep_aabb(&Vector3::new(1f64, 0f64, 0f64), &b), None);
+    assert_eq!(a.sweep_aabb(&Vector3::new(4f64, 0f64, 10f64), &b), None);
+    assert_eq!(a.sweep_aabb(&Vector3::new(0f64, 4f64, 0f64), &b), None);
+
+    let c = Aabb3::new(Point3::new(0.9f64, 0.5f64, 0.5f64), Point3::new(2f64, 2f64, 2f64));
+    assert_eq!(a.sweep_aabb(&Vector3::new(4f64, 0f64, 0f64), &c), Some((0f64, Vector3::new(-1f64, 0f64, 0f64))));
+}
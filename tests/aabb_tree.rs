@@ -0,0 +1,78 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> {
+    v.sort();
+    v
+}
+
+#[test]
+fn test_aabb_tree2() {
+    let mut tree: AabbTree2<f64, char> = AabbTree::new(0.1f64);
+    let a = tree.insert(&Aabb2::new(Point2::new(0f64, 0f64), Point2::new(1f64, 1f64)), 'a');
+    let b = tree.insert(&Aabb2::new(Point2::new(0.5f64, 0.5f64), Point2::new(1.5f64, 1.5f64)), 'b');
+    let c = tree.insert(&Aabb2::new(Point2::new(5f64, 5f64), Point2::new(6f64, 6f64)), 'c');
+    assert_eq!(tree.len(), 3);
+    assert_eq!(*tree.get(c), 'c');
+    assert_eq!(*tree.fat_aabb(a), Aabb2::new(Point2::new(-0.1f64, -0.1f64), Point2::new(1.1f64, 1.1f64)));
+
+    assert_eq!(tree.overlapping_pairs(), vec![(a, b)]);
+    assert_eq!(tree.query(&Aabb2::new(Point2::new(5.5f64, 5.5f64), Point2::new(5.5f64, 5.5f64))), vec![c]);
+
+    // small movements stay within the fattened box
+    assert!(!tree.move_object(c, &Aabb2::new(Point2::new(5.05f64, 5f64), Point2::new(6.05f64, 6f64)),
+                              &Vector2::new(0.05f64, 0f64)));
+    assert!(tree.move_object(c, &Aabb2::new(Point2::new(0.8f64, 0.8f64), Point2::new(1.8f64, 1.8f64)),
+                             &Vector2::new(-4.25f64, -4.2f64)));
+    assert_eq!(sorted(tree.overlapping_pairs()), sorted(vec![(a, b), (a, c), (b, c)]));
+
+    assert_eq!(tree.remove(b), 'b');
+    assert_eq!(tree.len(), 2);
+    assert_eq!(tree.overlapping_pairs(), vec![(a, c)]);
+}
+
+#[test]
+fn test_aabb_tree3() {
+    let mut tree: AabbTree3<f64, (uint, uint)> = AabbTree::new(0.1f64);
+    let mut ids = Vec::new();
+    for i in range(0u, 10) {
+        for j in range(0u, 10) {
+            let min = Point3::new(2f64 * i as f64, 2f64 * j as f64, 0f64);
+            let aabb = Aabb3::new(min, min.add_v(&Vector3::new(1f64, 1f64, 1f64)));
+            ids.push(tree.insert(&aabb, (i, j)));
+        }
+    }
+    assert_eq!(tree.len(), 100);
+    assert!(tree.overlapping_pairs().is_empty());
+
+    let query = Aabb3::new(Point3::new(0f64, 0f64, 0f64), Point3::new(4f64, 4f64, 1f64));
+    assert_eq!(tree.query(&query).len(), 9);
+
+    // remove the boxes in every other column
+    for &id in ids.iter() {
+        let (i, _) = *tree.get(id);
+        if i % 2 == 0 { tree.remove(id); }
+    }
+    assert_eq!(tree.len(), 50);
+    let found = tree.query(&query);
+    assert_eq!(found.len(), 3);
+    assert!(found.iter().all(|&id| { let (i, _) = *tree.get(id); i == 1 }));
+}
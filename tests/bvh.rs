@@ -0,0 +1,99 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+/// A row of unit boxes along the x axis, with each box's index as its payload.
+fn unit_box(i: uint, y: f64) -> Aabb3<f64> {
+    let x = i as f64;
+    Aabb3::new(Point3::new(x, y, 0f64), Point3::new(x + 1f64, y + 1f64, 1f64))
+}
+
+fn build(split: BvhSplit) -> Bvh<f64, uint> {
+    let items: Vec<(Aabb3<f64>, uint)> = range(0u, 10).map(|i| (unit_box(i, 0f64), i)).collect();
+    Bvh::new(items.as_slice(), split)
+}
+
+fn sorted(found: Vec<&uint>) -> Vec<uint> {
+    let mut v: Vec<uint> = found.iter().map(|&&i| i).collect();
+    v.sort();
+    v
+}
+
+#[test]
+fn test_bvh_queries() {
+    for &split in [MedianSplit, SahSplit].iter() {
+        let bvh = build(split);
+        assert_eq!(bvh.len(), 10);
+        assert_eq!(bvh.aabb(), Some(Aabb3::new(Point3::new(0f64, 0f64, 0f64), Point3::new(10f64, 1f64, 1f64))));
+
+        let query = Aabb3::new(Point3::new(2.5f64, 0.5f64, 0.5f64), Point3::new(3.5f64, 2f64, 2f64));
+        assert_eq!(sorted(bvh.query_aabb(&query)), vec![2, 3]);
+
+        let hit = Ray::new(Point3::new(-5f64, 0.5f64, 0.5f64), Vector3::new(1f64, 0f64, 0f64));
+        let hits = bvh.query_ray(&hit);
+        assert_eq!(hits.len(), 10);
+        assert_eq!(hits[0], (5f64, &0u));
+        assert_eq!(hits[9], (14f64, &9u));
+        let miss = Ray::new(Point3::new(-5f64, 5f64, 0.5f64), Vector3::new(1f64, 0f64, 0f64));
+        assert!(bvh.query_ray(&miss).is_empty());
+
+        assert_eq!(bvh.nearest(&Point3::new(20f64, 0.5f64, 0.5f64)), Some((10f64, &9u)));
+        assert_eq!(bvh.nearest(&Point3::new(4.5f64, 3f64, 0.5f64)), Some((2f64, &4u)));
+
+        let frustum = Frustum::new(Plane::from_abcd(1f64, 0f64, 0f64, -2.5f64),
+                                   Plane::from_abcd(-1f64, 0f64, 0f64, 5.5f64),
+                                   Plane::from_abcd(0f64, 1f64, 0f64, 1f64),
+                                   Plane::from_abcd(0f64, -1f64, 0f64, 2f64),
+                                   Plane::from_abcd(0f64, 0f64, -1f64, 2f64),
+                                   Plane::from_abcd(0f64, 0f64, 1f64, 2f64));
+        assert_eq!(sorted(bvh.query_frustum(&frustum)), vec![2, 3, 4, 5]);
+    }
+}
+
+#[test]
+fn test_bvh_refit() {
+    let mut bvh = build(SahSplit);
+    bvh.refit(|&i| unit_box(i, 10f64));
+    assert_eq!(bvh.aabb(), Some(Aabb3::new(Point3::new(0f64, 10f64, 0f64), Point3::new(10f64, 11f64, 1f64))));
+
+    let old = Aabb3::new(Point3::new(0f64, 0f64, 0f64), Point3::new(10f64, 1f64, 1f64));
+    let new = Aabb3::new(Point3::new(6.5f64, 10.5f64, 0.5f64), Point3::new(6.5f64, 10.5f64, 0.5f64));
+    assert!(bvh.query_aabb(&old).is_empty());
+    assert_eq!(sorted(bvh.query_aabb(&new)), vec![6]);
+}
+
+#[test]
+fn test_bvh_empty() {
+    let bvh: Bvh<f64, uint> = Bvh::new(&[], MedianSplit);
+    assert!(bvh.is_empty());
+    assert_eq!(bvh.aabb(), None);
+    assert_eq!(bvh.nearest(&Point3::new(0f64, 0f64, 0f64)), None);
+}
+
+#[test]
+fn test_bvh_coincident() {
+    // every SAH split of coincident boxes costs the same, which should not
+    // make the tree degenerate into a list
+    let items: Vec<(Aabb3<f64>, uint)> = range(0u, 20000).map(|i| (unit_box(0, 0f64), i)).collect();
+    let bvh = Bvh::new(items.as_slice(), SahSplit);
+    assert_eq!(bvh.len(), 20000);
+    assert_eq!(bvh.query_aabb(&unit_box(0, 0f64)).len(), 20000);
+    assert!(bvh.query_aabb(&unit_box(5, 0f64)).is_empty());
+}
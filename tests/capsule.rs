@@ -0,0 +1,63 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_capsule() {
+    let cap = Capsule::from_points(Point3::new(0f64, 0f64, -1f64), Point3::new(0f64, 0f64, 1f64), 1f64);
+    assert_eq!(cap.center, Point3::new(0f64, 0f64, 0f64));
+    assert_eq!(cap.axis, Vector3::new(0f64, 0f64, 1f64));
+
+    let aabb = cap.to_aabb3();
+    assert_eq!(aabb.min, Point3::new(-1f64, -1f64, -2f64));
+    assert_eq!(aabb.max, Point3::new(1f64, 1f64, 2f64));
+    assert_eq!(cap.to_sphere().radius, 2f64);
+
+    assert!(cap.contains(&Point3::new(0f64, 0f64, 1.9f64)));
+    assert!(!cap.contains(&Point3::new(0.8f64, 0f64, 1.8f64)));
+    assert_eq!(cap.closest_point(&Point3::new(0f64, 0f64, 5f64)), Point3::new(0f64, 0f64, 2f64));
+    assert_eq!(cap.closest_point(&Point3::new(3f64, 0f64, 0.5f64)), Point3::new(1f64, 0f64, 0.5f64));
+}
+
+#[test]
+fn test_capsule_ray() {
+    let cap = Capsule::from_points(Point3::new(0f64, 0f64, -1f64), Point3::new(0f64, 0f64, 1f64), 1f64);
+
+    let r = Ray::new(Point3::new(0f64, 0f64, 5f64), Vector3::new(0f64, 0f64, -1f64));
+    assert_eq!((cap, r).intersection(), Some((3f64, 7f64)));
+    let r = Ray::new(Point3::new(5f64, 0f64, 0f64), Vector3::new(-1f64, 0f64, 0f64));
+    assert_eq!((cap, r).intersection(), Some((4f64, 6f64)));
+    let r = Ray::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(0f64, 0f64, 1f64));
+    assert_eq!((cap, r).intersection_point(), Some(Point3::new(0f64, 0f64, 2f64)));
+    let r = Ray::new(Point3::new(5f64, 0f64, 2.5f64), Vector3::new(-1f64, 0f64, 0f64));
+    assert_eq!((cap, r).intersection(), None);
+}
+
+#[test]
+fn test_capsule_overlap() {
+    let cap = Capsule::from_points(Point3::new(0f64, 0f64, -1f64), Point3::new(0f64, 0f64, 1f64), 1f64);
+    let near = Capsule::from_points(Point3::new(1.5f64, -1f64, 0f64), Point3::new(1.5f64, 1f64, 0f64), 0.6f64);
+    let far = Capsule::from_points(Point3::new(2.5f64, -1f64, 0f64), Point3::new(2.5f64, 1f64, 0f64), 0.6f64);
+    assert!((cap, near).intersection());
+    assert!(!(cap, far).intersection());
+
+    assert!((cap, Sphere::new(Point3::new(0f64, 0f64, 2.5f64), 0.6f64)).intersection());
+    assert!(!(cap, Sphere::new(Point3::new(0f64, 0f64, 2.5f64), 0.4f64)).intersection());
+}
@@ -0,0 +1,79 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_cylinder() {
+    let cyl = Cylinder::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(0f64, 0f64, 2f64), 1f64);
+
+    let aabb = cyl.to_aabb3();
+    assert!(aabb.min.approx_eq(&Point3::new(-1f64, -1f64, -2f64)));
+    assert!(aabb.max.approx_eq(&Point3::new(1f64, 1f64, 2f64)));
+    assert!(cyl.to_sphere().radius.approx_eq(&5f64.sqrt()));
+
+    assert!(cyl.contains(&Point3::new(0.5f64, 0.5f64, 1.5f64)));
+    assert!(!cyl.contains(&Point3::new(0.8f64, 0.8f64, 0f64)));
+    assert!(!cyl.contains(&Point3::new(0f64, 0f64, 2.5f64)));
+
+    assert!(cyl.closest_point(&Point3::new(3f64, 0f64, 3f64)).approx_eq(&Point3::new(1f64, 0f64, 2f64)));
+    assert!(cyl.closest_point(&Point3::new(0f64, -3f64, 1f64)).approx_eq(&Point3::new(0f64, -1f64, 1f64)));
+}
+
+#[test]
+fn test_cylinder_zero_axis() {
+    let cyl = Cylinder::new(Point3::new(1f64, 0f64, 0f64), Vector3::new(0f64, 0f64, 0f64), 1f64);
+    assert!(cyl.closest_point(&Point3::new(1f64, 0f64, 3f64)).approx_eq(&Point3::new(1f64, 0f64, 1f64)));
+    assert!(cyl.closest_point(&Point3::new(4f64, 4f64, 0f64)).approx_eq(&Point3::new(1.6f64, 0.8f64, 0f64)));
+    assert_eq!(cyl.closest_point(&Point3::new(1.5f64, 0f64, 0f64)), Point3::new(1.5f64, 0f64, 0f64));
+
+    assert!(cyl.contains(&Point3::new(1f64, 0f64, 0.5f64)));
+    assert!(cyl.contains(&Point3::new(1f64, 1f64, 0f64)));
+    assert!(!cyl.contains(&Point3::new(1f64, 0.8f64, 0.8f64)));
+
+    let aabb = cyl.to_aabb3();
+    assert_eq!(aabb.min, Point3::new(0f64, -1f64, -1f64));
+    assert_eq!(aabb.max, Point3::new(2f64, 1f64, 1f64));
+
+    let r = Ray::new(Point3::new(1f64, 0f64, 5f64), Vector3::new(0f64, 0f64, -1f64));
+    assert_eq!((cyl, r).intersection(), Some((4f64, 6f64)));
+    let miss = Ray::new(Point3::new(3f64, 0f64, 5f64), Vector3::new(0f64, 0f64, -1f64));
+    assert_eq!((cyl, miss).intersection(), None);
+
+    assert!(cyl.support(&Vector3::new(0f64, 0f64, 3f64)).approx_eq(&Point3::new(1f64, 0f64, 1f64)));
+    assert!(cyl.support(&Vector3::new(-1f64, 0f64, 0f64)).approx_eq(&Point3::new(0f64, 0f64, 0f64)));
+}
+
+#[test]
+fn test_cylinder_ray() {
+    let cyl = Cylinder::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(0f64, 0f64, 2f64), 1f64);
+
+    // through the side
+    let r = Ray::new(Point3::new(5f64, 0f64, 1f64), Vector3::new(-1f64, 0f64, 0f64));
+    assert_eq!((cyl, r).intersection(), Some((4f64, 6f64)));
+    // through the caps
+    let r = Ray::new(Point3::new(0f64, 0.5f64, 5f64), Vector3::new(0f64, 0f64, -1f64));
+    assert_eq!((cyl, r).intersection(), Some((3f64, 7f64)));
+    // from inside
+    let r = Ray::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(0f64, 1f64, 0f64));
+    assert_eq!((cyl, r).intersection_point(), Some(Point3::new(0f64, 1f64, 0f64)));
+    // passing over the top cap
+    let r = Ray::new(Point3::new(5f64, 0f64, 3f64), Vector3::new(-1f64, 0f64, 0f64));
+    assert_eq!((cyl, r).intersection(), None);
+}
@@ -0,0 +1,85 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_lu() {
+    let a = Matrix3::new(0.0f64, 2.0, 1.0,
+                         1.0, 1.0, 0.0,
+                         3.0, 0.0, 2.0);
+    let lu = a.lu();
+    assert!(!lu.is_singular());
+    assert!(lu.p().mul_m(&a).approx_eq(&lu.l().mul_m(&lu.u())));
+    assert!(lu.determinant().approx_eq(&a.determinant()));
+    assert!(lu.invert().unwrap().approx_eq(&a.invert().unwrap()));
+
+    let b = Vector3::new(1.0f64, 2.0, 3.0);
+    let x = a.solve(&b).unwrap();
+    assert!(a.mul_v(&x).approx_eq(&b));
+
+    let singular = Matrix2::new(1.0f64, 2.0, 2.0, 4.0);
+    assert!(singular.lu().is_singular());
+    assert_eq!(singular.solve(&Vector2::new(1.0, 1.0)), None);
+    assert!(singular.condition_number().is_infinite());
+}
+
+#[test]
+fn test_qr() {
+    let a = Matrix4::new(4.0f64, 3.0, 2.0, 1.0,
+                         3.0, 4.0, 3.0, 2.0,
+                         2.0, 3.0, 4.0, 3.0,
+                         1.0, 2.0, 3.0, 5.0);
+    let qr = a.qr();
+    let q = qr.q();
+    assert!(q.transpose().mul_m(&q).approx_eq(&Matrix4::identity()));
+    assert!(a.mul_m(&qr.p()).approx_eq(&q.mul_m(&qr.r())));
+    let r = qr.r();
+    assert!(r[0][1] == 0.0 && r[1][2] == 0.0 && r[2][3] == 0.0);
+    assert_eq!(a.rank(), 4);
+
+    let b = Vector4::new(1.0f64, 2.0, 3.0, 4.0);
+    assert!(a.solve_least_squares(&b).approx_eq(&a.solve(&b).unwrap()));
+}
+
+#[test]
+fn test_least_squares() {
+    // the second column is twice the first, so only the sums of the first
+    // two components of `x` are determined
+    let a = Matrix3::new(1.0f64, 2.0, 3.0,
+                         2.0, 4.0, 6.0,
+                         0.0, 1.0, 1.0);
+    assert_eq!(a.rank(), 2);
+    assert_eq!(a.solve(&Vector3::new(1.0, 2.0, 3.0)), None);
+
+    let b = Vector3::new(1.0f64, 2.0, 3.0);
+    let x = a.solve_least_squares(&b);
+    assert!(a.mul_v(&x).approx_eq(&b));
+
+    // a point that is not in the range of the matrix is projected onto it
+    let b = Vector3::new(1.0f64, 0.0, 0.0);
+    let x = a.solve_least_squares(&b);
+    let residual = a.mul_v(&x).sub_v(&b);
+    assert!(residual.dot(&a.x).approx_eq(&0.0));
+    assert!(residual.dot(&a.z).approx_eq(&0.0));
+
+    assert_eq!(Matrix2::<f64>::zero().rank(), 0);
+    assert!(Matrix3::<f64>::identity().condition_number().approx_eq(&1.0));
+    assert!(Matrix2::new(1.0f64, 0.0, 0.0, 1e-3).condition_number().approx_eq(&1e3));
+}
@@ -0,0 +1,71 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+fn assert_points_approx_eq(a: &FrustumPoints<f64>, b: &FrustumPoints<f64>) {
+    assert!(a.near_top_left.approx_eq(&b.near_top_left));
+    assert!(a.near_top_right.approx_eq(&b.near_top_right));
+    assert!(a.near_bottom_left.approx_eq(&b.near_bottom_left));
+    assert!(a.near_bottom_right.approx_eq(&b.near_bottom_right));
+    assert!(a.far_top_left.approx_eq(&b.far_top_left));
+    assert!(a.far_top_right.approx_eq(&b.far_top_right));
+    assert!(a.far_bottom_left.approx_eq(&b.far_bottom_left));
+    assert!(a.far_bottom_right.approx_eq(&b.far_bottom_right));
+}
+
+#[test]
+fn test_frustum_points() {
+    let proj = PerspectiveFov { fovy: deg(90f64), aspect: 1f64, near: 1f64, far: 10f64 };
+    let points = proj.to_frustum_points();
+    assert!(points.near_top_left.approx_eq(&Point3::new(-1f64, 1f64, -1f64)));
+    assert!(points.far_bottom_right.approx_eq(&Point3::new(10f64, -10f64, -10f64)));
+
+    let from_planes = proj.to_frustum().to_points().unwrap();
+    assert_points_approx_eq(&points, &from_planes);
+
+    let from_matrix = FrustumPoints::from_inverse_matrix4(proj.to_matrix4().invert().unwrap());
+    assert_points_approx_eq(&points, &from_matrix);
+
+    let aabb = points.to_aabb3();
+    assert!(aabb.min.approx_eq(&Point3::new(-10f64, -10f64, -10f64)));
+    assert!(aabb.max.approx_eq(&Point3::new(10f64, 10f64, -1f64)));
+}
+
+#[test]
+fn test_frustum_contains() {
+    let proj = PerspectiveFov { fovy: deg(90f64), aspect: 1f64, near: 1f64, far: 10f64 };
+    let frustum = proj.to_frustum();
+
+    assert_eq!(frustum.contains(&Point3::new(0f64, 0f64, -5f64)), In);
+    assert_eq!(frustum.contains(&Point3::new(0f64, 0f64, 5f64)), Out);
+
+    assert_eq!(frustum.contains(&Sphere { center: Point3::new(0f64, 0f64, -5f64), radius: 1f64 }), In);
+    assert_eq!(frustum.contains(&Sphere { center: Point3::new(0f64, 0f64, -1f64), radius: 0.5f64 }), Cross);
+    assert_eq!(frustum.contains(&Sphere { center: Point3::new(0f64, 0f64, 5f64), radius: 1f64 }), Out);
+
+    assert_eq!(frustum.contains(&Aabb3::new(Point3::new(-1f64, -1f64, -6f64), Point3::new(1f64, 1f64, -4f64))), In);
+    assert_eq!(frustum.contains(&Aabb3::new(Point3::new(-1f64, -1f64, -12f64), Point3::new(1f64, 1f64, -8f64))), Cross);
+    assert_eq!(frustum.contains(&Aabb3::new(Point3::new(20f64, -1f64, -6f64), Point3::new(22f64, 1f64, -4f64))), Out);
+
+    // only the far plane is crossed, so children need only be tested against it
+    let aabb = Aabb3::new(Point3::new(-1f64, -1f64, -12f64), Point3::new(1f64, 1f64, -8f64));
+    assert_eq!(frustum.contains_masked(&aabb, 0b111111), (Cross, 0b100000));
+    assert_eq!(frustum.contains_masked(&aabb, 0b011111), (In, 0));
+}
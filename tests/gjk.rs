@@ -0,0 +1,88 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_support() {
+    let aabb = Aabb3::new(Point3::new(0f64, 0f64, 0f64), Point3::new(1f64, 2f64, 3f64));
+    assert_eq!(aabb.support(&Vector3::new(1f64, -1f64, 1f64)), Point3::new(1f64, 0f64, 3f64));
+
+    let sphere = Sphere::new(Point3::new(1f64, 0f64, 0f64), 2f64);
+    assert!(sphere.support(&Vector3::new(0f64, 3f64, 0f64)).approx_eq(&Point3::new(1f64, 2f64, 0f64)));
+
+    let cylinder = Cylinder::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(0f64, 0f64, 2f64), 1f64);
+    assert!(cylinder.support(&Vector3::new(1f64, 0f64, -1f64)).approx_eq(&Point3::new(1f64, 0f64, -2f64)));
+
+    let points = vec![Point3::new(0f64, 0f64, 0f64), Point3::new(1f64, 1f64, 0f64), Point3::new(-1f64, 2f64, 0f64)];
+    assert_eq!(points.support(&Vector3::unit_y()), Point3::new(-1f64, 2f64, 0f64));
+}
+
+#[test]
+fn test_gjk_distance() {
+    let a = Sphere::new(Point3::new(0f64, 0f64, 0f64), 1f64);
+    let b = Aabb3::new(Point3::new(2f64, -1f64, -1f64), Point3::new(3f64, 1f64, 1f64));
+    let (dist, pa, pb) = gjk_distance(&a, &b).unwrap();
+    assert!(dist.approx_eq(&1f64));
+    assert!(pa.approx_eq(&Point3::new(1f64, 0f64, 0f64)));
+    assert!(pb.approx_eq(&Point3::new(2f64, 0f64, 0f64)));
+    assert!(!gjk_intersects(&a, &b));
+
+    let c = Aabb3::new(Point3::new(1f64, 1f64, 1f64), Point3::new(3f64, 3f64, 3f64));
+    let (dist, _, pc) = gjk_distance(&a, &c).unwrap();
+    assert!(dist.approx_eq_eps(&(3f64.sqrt() - 1f64), &1e-4f64));
+    assert!(pc.approx_eq(&Point3::new(1f64, 1f64, 1f64)));
+
+    let t = Triangle3::new(Point3::new(0f64, 0f64, 2f64), Point3::new(1f64, 0f64, 2f64), Point3::new(0f64, 1f64, 2f64));
+    assert!(gjk_distance(&t, &b).unwrap().val0().approx_eq(&2f64.sqrt()));
+
+    let d = Sphere::new(Point3::new(1.5f64, 0f64, 0f64), 1f64);
+    assert!(gjk_distance(&a, &d).is_none());
+    assert!(gjk_intersects(&a, &d));
+}
+
+#[test]
+fn test_epa_penetration() {
+    let a = Aabb3::new(Point3::new(0f64, 0f64, 0f64), Point3::new(1f64, 1f64, 1f64));
+    let b = Aabb3::new(Point3::new(0.8f64, 0.2f64, 0.1f64), Point3::new(2f64, 0.9f64, 0.9f64));
+    let p = epa_penetration(&a, &b).unwrap();
+    assert!(p.normal.approx_eq(&Vector3::unit_x()));
+    assert!(p.depth.approx_eq(&0.2f64));
+
+    let s = Sphere::new(Point3::new(0f64, 0f64, 0f64), 1f64);
+    let t = Sphere::new(Point3::new(1.5f64, 0f64, 0f64), 1f64);
+    let p = epa_penetration(&s, &t).unwrap();
+    assert!(p.normal.approx_eq_eps(&Vector3::unit_x(), &1e-2f64));
+    assert!(p.depth.approx_eq_eps(&0.5f64, &1e-4f64));
+
+    let far = Sphere::new(Point3::new(3f64, 0f64, 0f64), 1f64);
+    assert!(epa_penetration(&s, &far).is_none());
+}
+
+#[test]
+fn test_empty_point_set() {
+    let s = Sphere::new(Point3::new(0f64, 0f64, 0f64), 1f64);
+    let empty: Vec<Point3<f64>> = Vec::new();
+    assert!(!empty.has_support());
+    assert!(s.has_support());
+    assert!(gjk_distance(&s, &empty).is_none());
+    assert!(gjk_distance(&empty.as_slice(), &s).is_none());
+    assert!(!gjk_intersects(&s, &empty));
+    assert!(epa_penetration(&empty, &s).is_none());
+}
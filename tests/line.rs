@@ -66,3 +66,97 @@ fn test_line_intersection() {
     let l9 = Line::new(Point2::new(-1.0f32, 0.0), Point2::new(-1.0, 0.0));
     assert_eq!((r9, l9).intersection_point(), None);
 }
+
+#[test]
+fn test_line_closest_points() {
+    let l1 = Line::new(Point3::new(0f64, 0f64, 0f64), Point3::new(2f64, 0f64, 0f64));
+    let l2 = Line::new(Point3::new(1f64, -1f64, 1f64), Point3::new(1f64, 1f64, 1f64));
+    let l3 = Line::new(Point3::new(3f64, -1f64, 1f64), Point3::new(3f64, 1f64, 1f64));
+    assert_eq!(l1.closest_points(&l2), (Point3::new(1f64, 0f64, 0f64), Point3::new(1f64, 0f64, 1f64)));
+    // the closest point is clamped to the end of the first segment
+    assert_eq!(l1.closest_points(&l3), (Point3::new(2f64, 0f64, 0f64), Point3::new(3f64, 0f64, 1f64)));
+
+    // the ray points away from the segment
+    let r1 = Ray::new(Point3::new(1f64, 0f64, 2f64), Vector3::new(0f64, 0f64, 1f64));
+    assert_eq!(l1.closest_points_ray(&r1), (Point3::new(1f64, 0f64, 0f64), Point3::new(1f64, 0f64, 2f64)));
+    assert_eq!(r1.closest_points_line(&l1), (Point3::new(1f64, 0f64, 2f64), Point3::new(1f64, 0f64, 0f64)));
+}
+
+#[test]
+fn test_short_line_closest_points() {
+    // short segments should not be mistaken for points
+    let l1 = Line::new(Point3::new(0f64, 0f64, 0f64), Point3::new(2e-3f64, 0f64, 0f64));
+    let l2 = Line::new(Point3::new(1e-3f64, -1e-3f64, 1e-3f64), Point3::new(1e-3f64, 1e-3f64, 1e-3f64));
+    let (a, b) = l1.closest_points(&l2);
+    assert!(a.approx_eq_eps(&Point3::new(1e-3f64, 0f64, 0f64), &1e-9f64));
+    assert!(b.approx_eq_eps(&Point3::new(1e-3f64, 0f64, 1e-3f64), &1e-9f64));
+
+    // a segment of zero length is still a point
+    let l3 = Line::new(Point3::new(1f64, 1f64, 0f64), Point3::new(1f64, 1f64, 0f64));
+    assert_eq!(l1.closest_points(&l3), (Point3::new(2e-3f64, 0f64, 0f64), Point3::new(1f64, 1f64, 0f64)));
+}
+
+#[test]
+fn test_ray_closest_points() {
+    let r1 = Ray::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(1f64, 0f64, 0f64));
+    let r2 = Ray::new(Point3::new(5f64, -3f64, 1f64), Vector3::new(0f64, 1f64, 0f64));
+    let r3 = Ray::new(Point3::new(-1f64, 1f64, 0f64), Vector3::new(-1f64, 0f64, 0f64));
+    assert_eq!(r1.closest_points(&r2), (Point3::new(5f64, 0f64, 0f64), Point3::new(5f64, 0f64, 1f64)));
+    // parallel rays pointing in opposite directions
+    assert_eq!(r1.closest_points(&r3), (Point3::new(0f64, 0f64, 0f64), Point3::new(-1f64, 1f64, 0f64)));
+}
+
+#[test]
+fn test_point_distance() {
+    let l = Line::new(Point2::new(0f64, 0f64), Point2::new(2f64, 0f64));
+    assert_eq!(l.distance(&Point2::new(1f64, 3f64)), 3f64);
+    assert_eq!(l.distance(&Point2::new(4f64, 0f64)), 2f64);
+    assert_eq!(l.closest_point(&Point2::new(-1f64, 1f64)), Point2::new(0f64, 0f64));
+
+    let r = Ray::new(Point2::new(0f64, 0f64), Vector2::new(1f64, 0f64));
+    assert_eq!(r.distance(&Point2::new(5f64, 2f64)), 2f64);
+    assert_eq!(r.distance(&Point2::new(-3f64, 4f64)), 5f64);
+
+    let l3 = Line::new(Point3::new(0f64, 0f64, 0f64), Point3::new(0f64, 0f64, 2f64));
+    let r3 = Ray::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(0f64, 0f64, 1f64));
+    assert_eq!(l3.distance2(&Point3::new(1f64, 1f64, 1f64)), 2f64);
+    assert_eq!(l3.distance(&Point3::new(0f64, 0f64, 5f64)), 3f64);
+    assert_eq!(r3.distance(&Point3::new(0f64, 3f64, 5f64)), 3f64);
+}
+
+#[test]
+fn test_line_line_intersection() {
+    let seg = |x0: f64, y0: f64, x1: f64, y1: f64| Line::new(Point2::new(x0, y0), Point2::new(x1, y1));
+
+    // crossing segments
+    assert!((seg(0.0, 0.0, 2.0, 2.0), seg(0.0, 2.0, 2.0, 0.0)).intersection() == Some(Crossing(Point2::new(1.0, 1.0))));
+    // the lines cross, but not within the first segment
+    assert!((seg(0.0, 0.0, 1.0, 1.0), seg(3.0, 0.0, 0.0, 3.0)).intersection() == None);
+    // parallel
+    assert!((seg(0.0, 0.0, 1.0, 0.0), seg(0.0, 1.0, 1.0, 1.0)).intersection() == None);
+
+    // collinear, overlapping in the direction of the first segment
+    assert!((seg(0.0, 0.0, 2.0, 0.0), seg(3.0, 0.0, 1.0, 0.0)).intersection() == Some(Overlap(seg(1.0, 0.0, 2.0, 0.0))));
+    // collinear, touching at one end
+    assert!((seg(0.0, 0.0, 1.0, 0.0), seg(1.0, 0.0, 2.0, 0.0)).intersection() == Some(Crossing(Point2::new(1.0, 0.0))));
+    // collinear, no overlap
+    assert!((seg(0.0, 0.0, 1.0, 0.0), seg(2.0, 0.0, 3.0, 0.0)).intersection() == None);
+
+    // the first segment is a point on the second
+    assert!((seg(1.0, 0.0, 1.0, 0.0), seg(0.0, 0.0, 2.0, 0.0)).intersection() == Some(Crossing(Point2::new(1.0, 0.0))));
+    assert!((seg(1.0, 1.0, 1.0, 1.0), seg(0.0, 0.0, 2.0, 0.0)).intersection() == None);
+}
+
+#[test]
+fn test_line_aabb_clip() {
+    let aabb = Aabb2::new(Point2::new(0f64, 0f64), Point2::new(4f64, 4f64));
+
+    let through = Line::new(Point2::new(-2f64, 2f64), Point2::new(6f64, 2f64));
+    assert!((through, aabb).intersection() == Some(Line::new(Point2::new(0f64, 2f64), Point2::new(4f64, 2f64))));
+
+    let inside = Line::new(Point2::new(1f64, 1f64), Point2::new(2f64, 3f64));
+    assert!((inside, aabb).intersection() == Some(inside));
+
+    let outside = Line::new(Point2::new(-2f64, 5f64), Point2::new(6f64, 5f64));
+    assert!((outside, aabb).intersection() == None);
+}
@@ -0,0 +1,82 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> {
+    v.sort();
+    v
+}
+
+#[test]
+fn test_quadtree() {
+    let mut tree: Quadtree<int, char> = LooseTree::new(Aabb2::new(Point2::new(0i, 0i), Point2::new(64i, 64i)), 4);
+    let a = tree.insert(&Aabb2::new(Point2::new(1i, 1i), Point2::new(3i, 3i)), 'a');
+    let b = tree.insert(&Aabb2::new(Point2::new(10i, 10i), Point2::new(40i, 40i)), 'b');
+    // partly outside of the bounds of the tree
+    let c = tree.insert(&Aabb2::new(Point2::new(60i, 60i), Point2::new(70i, 70i)), 'c');
+    assert_eq!(tree.len(), 3);
+    assert_eq!(*tree.get(b), 'b');
+
+    assert_eq!(tree.query_point(&Point2::new(2i, 2i)), vec![a]);
+    assert_eq!(tree.query_point(&Point2::new(65i, 65i)), vec![c]);
+    assert_eq!(sorted(tree.query_aabb(&Aabb2::new(Point2::new(0i, 0i), Point2::new(12i, 12i)))), vec![a, b]);
+    assert_eq!(tree.query_radius(&Point2::new(5i, 5i), 3i), vec![a]);
+
+    assert_eq!(tree.remove(a), 'a');
+    assert_eq!(tree.len(), 2);
+    assert!(tree.query_point(&Point2::new(2i, 2i)).is_empty());
+    let d = tree.insert(&Aabb2::new(Point2::new(2i, 2i), Point2::new(4i, 4i)), 'd');
+    assert_eq!(tree.query_point(&Point2::new(2i, 2i)), vec![d]);
+}
+
+#[test]
+fn test_octree() {
+    let mut tree: Octree<f64, uint> = LooseTree::new(Aabb3::new(Point3::new(0f64, 0f64, 0f64),
+                                                                Point3::new(32f64, 32f64, 32f64)), 5);
+    let mut ids = Vec::new();
+    for i in range(0u, 10) {
+        let min = Point3::new(2f64 * i as f64, 0f64, 0f64);
+        ids.push(tree.insert(&Aabb3::new(min, min.add_v(&Vector3::new(1f64, 1f64, 1f64))), i));
+    }
+
+    let ray = Ray::new(Point3::new(-1f64, 0.5f64, 0.5f64), Vector3::unit_x());
+    let hits = tree.query_ray(&ray);
+    assert_eq!(hits.len(), 10);
+    assert_eq!(hits[0], (1f64, ids[0]));
+    assert_eq!(hits[1], (3f64, ids[1]));
+    let inside = Ray::new(Point3::new(6.5f64, 0.5f64, 0.5f64), Vector3::unit_x());
+    let hits = tree.query_ray(&inside);
+    assert_eq!(hits[0], (0f64, ids[3]));
+    assert_eq!(hits[1], (1.5f64, ids[4]));
+
+    let sphere = Sphere::new(Point3::new(4.5f64, 0.5f64, 3f64), 2f64);
+    assert_eq!(tree.query_sphere(&sphere), vec![ids[2]]);
+
+    let frustum = Frustum::new(Plane::from_abcd(1f64, 0f64, 0f64, -2.5f64),
+                               Plane::from_abcd(-1f64, 0f64, 0f64, 5.5f64),
+                               Plane::from_abcd(0f64, 1f64, 0f64, 1f64),
+                               Plane::from_abcd(0f64, -1f64, 0f64, 2f64),
+                               Plane::from_abcd(0f64, 0f64, -1f64, 2f64),
+                               Plane::from_abcd(0f64, 0f64, 1f64, 2f64));
+    assert_eq!(sorted(tree.query_frustum(&frustum)), vec![ids[1], ids[2]]);
+
+    assert_eq!(tree.remove(ids[2]), 2);
+    assert!(tree.query_sphere(&sphere).is_empty());
+}
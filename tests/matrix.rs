@@ -409,3 +409,73 @@ fn test_from_angle() {
     let rot3: Matrix2<f64> = Matrix2::from_angle(rad(Float::pi()));
     assert!(rot3.mul_v(&Vector2::new(1.0, 1.0)).approx_eq(&Vector2::new(-1.0, -1.0)));
 }
+
+#[test]
+fn test_symmetric_eigen() {
+    let (values, rot) = matrix2::C.symmetric_eigen();
+    assert!(values.approx_eq(&Vector2::new(3.0f64, 1.0f64)));
+    let m = rot.as_matrix2();
+    for i in range(0u, 2) {
+        assert!(matrix2::C.mul_v(&m[i]).approx_eq(&m[i].mul_s(values[i])));
+    }
+    assert!(m.determinant().approx_eq(&1.0f64));
+
+    let (values, rot) = matrix3::D.symmetric_eigen();
+    assert!(values.x >= values.y && values.y >= values.z);
+    assert!((values.x + values.y + values.z).approx_eq(&matrix3::D.trace()));
+    let m = rot.as_matrix3();
+    for i in range(0u, 3) {
+        assert!(matrix3::D.mul_v(&m[i]).approx_eq(&m[i].mul_s(values[i])));
+    }
+    assert!(m.determinant().approx_eq(&1.0f64));
+
+    let diagonal = Matrix3::from_diagonal(&Vector3::new(1.0f64, 5.0f64, 2.0f64));
+    let (values, _) = diagonal.symmetric_eigen();
+    assert!(values.approx_eq(&Vector3::new(5.0f64, 2.0f64, 1.0f64)));
+
+    // rounding limits how far the off-diagonal elements can be reduced in
+    // single precision
+    let single = Matrix3::new(4.0f32, 1.0f32, 2.0f32,
+                              1.0f32, 3.0f32, 0.0f32,
+                              2.0f32, 0.0f32, 5.0f32);
+    let (values, rot) = single.symmetric_eigen();
+    assert!((values.x + values.y + values.z).approx_eq_eps(&12.0f32, &1e-4f32));
+    let m = rot.as_matrix3();
+    for i in range(0u, 3) {
+        assert!(single.mul_v(&m[i]).approx_eq_eps(&m[i].mul_s(values[i]), &1e-4f32));
+    }
+}
+
+#[test]
+fn test_svd() {
+    let reflection = Matrix3::from_diagonal(&Vector3::new(1.0f64, -1.0f64, 1.0f64)).mul_m(&matrix3::D);
+    for m in [matrix3::C, matrix3::D, reflection].iter() {
+        let (u, sigma, v) = m.svd();
+        let (u, v) = (u.as_matrix3(), v.as_matrix3());
+        assert!(u.determinant().approx_eq(&1.0f64));
+        assert!(v.determinant().approx_eq(&1.0f64));
+        assert!(sigma.x >= sigma.y.abs() && sigma.y.abs() >= sigma.z.abs());
+        assert!(u.mul_m(&Matrix3::from_diagonal(&sigma)).mul_m(&v.transpose()).approx_eq(m));
+    }
+    let (_, sigma, _) = reflection.svd();
+    assert!(sigma.z < 0.0f64);
+
+    let (_, sigma, _) = Matrix3::from_value(2.0f64).svd();
+    assert!(sigma.approx_eq(&Vector3::new(2.0f64, 2.0f64, 2.0f64)));
+}
+
+#[test]
+fn test_polar_decomposition() {
+    let rot = Matrix3::from_axis_angle(&Vector3::new(1.0f64, 2.0f64, 3.0f64).normalize(), rad(0.7f64));
+    let stretch = Matrix3::from_diagonal(&Vector3::new(2.0f64, 1.0f64, 0.5f64));
+    let (r, s) = rot.mul_m(&stretch).polar_decomposition();
+    assert!(r.as_matrix3().approx_eq(&rot));
+    assert!(s.approx_eq(&stretch));
+
+    // a rotation that has drifted is re-orthonormalized
+    let drifted = rot.add_m(&Matrix3::from_value(1e-3f64));
+    let (r, s) = drifted.polar_decomposition();
+    assert!(s.is_symmetric());
+    assert!(r.as_matrix3().mul_m(&s).approx_eq(&drifted));
+    assert!(r.as_matrix3().mul_m(&r.as_matrix3().transpose()).approx_eq(&Matrix3::identity()));
+}
@@ -0,0 +1,84 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_obb2() {
+    let rot: Basis2<f64> = Rotation2::from_angle(deg(45f64).to_rad());
+    let obb = Obb2::new(Point2::new(0f64, 0f64), rot, Vector2::new(1f64, 1f64));
+    let s = 2f64.sqrt();
+
+    assert!(obb.corners()[0].approx_eq(&Point2::new(0f64, -s)));
+    assert!(obb.contains(&Point2::new(1.2f64, 0f64)));
+    assert!(!obb.contains(&Point2::new(1f64, 1f64)));
+    assert!(obb.closest_point(&Point2::new(2f64, 2f64)).approx_eq(&Point2::new(s / 2f64, s / 2f64)));
+
+    let aabb = obb.to_aabb2();
+    assert!(aabb.min.approx_eq(&Point2::new(-s, -s)));
+    assert!(aabb.max.approx_eq(&Point2::new(s, s)));
+
+    assert!((obb, Aabb2::new(Point2::new(1f64, -0.1f64), Point2::new(2f64, 0.1f64))).intersection());
+    assert!(!(obb, Aabb2::new(Point2::new(0.8f64, 0.8f64), Point2::new(2f64, 2f64))).intersection());
+}
+
+#[test]
+fn test_obb3() {
+    let rot: Basis3<f64> = Rotation3::from_angle_z(deg(45f64).to_rad());
+    let obb = Obb3::new(Point3::new(0f64, 0f64, 0f64), rot, Vector3::new(1f64, 1f64, 1f64));
+    let s = 2f64.sqrt();
+
+    assert!(obb.corners()[0].approx_eq(&Point3::new(0f64, -s, -1f64)));
+    assert!(obb.contains(&Point3::new(1.2f64, 0f64, 0.5f64)));
+    assert!(!obb.contains(&Point3::new(1f64, 1f64, 0f64)));
+    assert!(obb.closest_point(&Point3::new(2f64, 2f64, 3f64)).approx_eq(&Point3::new(s / 2f64, s / 2f64, 1f64)));
+
+    let aabb = obb.to_aabb3();
+    assert!(aabb.min.approx_eq(&Point3::new(-s, -s, -1f64)));
+    assert!(aabb.max.approx_eq(&Point3::new(s, s, 1f64)));
+
+    assert!((obb, Aabb3::new(Point3::new(1f64, -0.1f64, -0.1f64), Point3::new(2f64, 0.1f64, 0.1f64))).intersection());
+    assert!(!(obb, Aabb3::new(Point3::new(0.8f64, 0.8f64, -1f64), Point3::new(2f64, 2f64, 1f64))).intersection());
+
+    let other = Obb3::new(Point3::new(2.3f64, 0f64, 0f64), Rotation::identity(), Vector3::new(1f64, 1f64, 1f64));
+    assert!((obb, other).intersection());
+    let other = Obb3::new(Point3::new(3f64, 0f64, 0f64), Rotation::identity(), Vector3::new(1f64, 1f64, 1f64));
+    assert!(!(obb, other).intersection());
+
+    let plane = Plane::new(Vector3::unit_x(), -1.2f64);
+    assert_eq!(plane.relate(&obb), Cross);
+}
+
+#[test]
+fn test_obb3_from_points() {
+    let rot: Basis3<f64> = Rotation3::from_angle_z(deg(30f64).to_rad());
+    let obb = Obb3::new(Point3::new(1f64, 2f64, 3f64), rot, Vector3::new(3f64, 1f64, 0.5f64));
+    let corners = obb.corners();
+    let fit = Obb3::from_points(&corners).unwrap();
+
+    assert!(fit.center.approx_eq(&obb.center));
+    assert!(fit.extents.approx_eq(&obb.extents));
+    let axis = Vector3::new(3f64.sqrt() / 2f64, 0.5f64, 0f64);
+    assert!(fit.axes()[0].dot(&axis).abs().approx_eq(&1f64));
+    for p in corners.iter() {
+        assert!(fit.contains(&p.add_v(&fit.center.sub_p(p).mul_s(1e-6f64))));
+    }
+    let empty: &[Point3<f64>] = &[];
+    assert!(Obb3::from_points(empty).is_none());
+}
@@ -44,3 +44,81 @@ fn test_ray_intersection() {
     let r1: Ray3<f64> = Ray::new(Point3::new(0f64, 0f64, 0f64), Vector3::new(-1f64, 0f64, 0f64).normalize());
     assert_eq!((p1, r1).intersection_point(), None); // r1 points away from p1
 }
+
+#[test]
+fn test_plane2_intersection() {
+    let p0 = Plane::new(Vector3::unit_x(), 1f64);
+    let p1 = Plane::new(Vector3::unit_y(), -2f64);
+    let r: Ray3<f64> = (p0, p1).intersection().unwrap();
+    assert!(r.origin.approx_eq(&Point3::new(-1f64, 2f64, 0f64)));
+    assert!(r.direction.approx_eq(&Vector3::unit_z()));
+
+    let p2 = Plane::new(Vector3::unit_x(), 3f64);
+    let r: Option<Ray3<f64>> = (p0, p2).intersection();
+    assert!(r.is_none()); // the planes are parallel
+
+    // the parallel test should not depend on the lengths of the normals
+    let p3 = Plane::new(Vector3::new(1e-3f64, 0f64, 0f64), 1e-3f64);
+    let p4 = Plane::new(Vector3::new(0f64, 1e-3f64, 0f64), -2e-3f64);
+    let r: Ray3<f64> = (p3, p4).intersection().unwrap();
+    assert!(r.origin.approx_eq(&Point3::new(-1f64, 2f64, 0f64)));
+    assert!(r.direction.approx_eq(&Vector3::unit_z()));
+
+    let p5 = Plane::new(Vector3::new(1e3f64, 0f64, 0f64), 0f64);
+    let p6 = Plane::new(Vector3::new(1e3f64, 1e-3f64, 0f64), 0f64);
+    let r: Option<Ray3<f64>> = (p5, p6).intersection();
+    assert!(r.is_none()); // the planes are nearly parallel
+}
+
+#[test]
+fn test_plane3_intersection() {
+    let p0 = Plane::new(Vector3::unit_x(), 1f64);
+    let p1 = Plane::new(Vector3::unit_y(), -2f64);
+    let p2 = Plane::new(Vector3::unit_z(), 3f64);
+    assert!((p0, p1, p2).intersection_point().unwrap().approx_eq(&Point3::new(-1f64, 2f64, -3f64)));
+
+    let p3 = Plane::new(Vector3::new(1f64, 1f64, 0f64).normalize(), 0f64);
+    assert_eq!((p0, p1, p3).intersection_point(), None); // the normals are coplanar
+
+    let p4 = Plane::new(Vector3::new(1e-2f64, 0f64, 0f64), 1e-2f64);
+    let p5 = Plane::new(Vector3::new(0f64, 1e-2f64, 0f64), -2e-2f64);
+    let p6 = Plane::new(Vector3::new(0f64, 0f64, 1e-2f64), 3e-2f64);
+    assert!((p4, p5, p6).intersection_point().unwrap().approx_eq(&Point3::new(-1f64, 2f64, -3f64)));
+}
+
+#[test]
+fn test_point_queries() {
+    let p = Plane::from_point_normal(Point3::new(0f64, 2f64, 0f64), Vector3::unit_y());
+    // n.p + d = 0, so d is the negated offset along the normal
+    assert_eq!(p.d, -2f64);
+    assert_eq!(Plane::from_point_normal(Point3::new(1f64, 0f64, 0f64), Vector3::new(-1f64, 0f64, 0f64)).d, 1f64);
+    assert_eq!(p.distance(&Point3::new(1f64, 5f64, 1f64)), 3f64);
+    assert_eq!(p.distance(&Point3::new(1f64, -1f64, 1f64)), -3f64);
+
+    assert_eq!(p.relate_point(&Point3::new(0f64, 3f64, 0f64)), In);
+    assert_eq!(p.relate_point(&Point3::new(0f64, 1f64, 0f64)), Out);
+    assert_eq!(p.relate_point(&Point3::new(7f64, 2f64, -7f64)), Cross);
+    assert_eq!(p.relate_point_eps(&Point3::new(0f64, 2.5f64, 0f64), &1f64), Cross);
+
+    assert_eq!(p.project_point(&Point3::new(1f64, 5f64, 1f64)), Point3::new(1f64, 2f64, 1f64));
+    assert_eq!(p.reflect_point(&Point3::new(1f64, 5f64, 1f64)), Point3::new(1f64, -1f64, 1f64));
+    assert_eq!(p.reflect_vector(&Vector3::new(1f64, -1f64, 0f64)), Vector3::new(1f64, 1f64, 0f64));
+}
+
+#[test]
+fn test_bound_queries() {
+    let p = Plane::from_point_normal(Point3::new(0f64, 2f64, 0f64), Vector3::unit_y());
+    assert_eq!(p.relate(&Sphere { center: Point3::new(0f64, 4f64, 0f64), radius: 1f64 }), In);
+    assert_eq!(p.relate(&Sphere { center: Point3::new(0f64, 2.5f64, 0f64), radius: 1f64 }), Cross);
+    assert_eq!(p.relate(&Sphere { center: Point3::new(0f64, 0f64, 0f64), radius: 1f64 }), Out);
+
+    assert_eq!(p.relate(&Aabb3::new(Point3::new(-1f64, 3f64, -1f64), Point3::new(1f64, 4f64, 1f64))), In);
+    assert_eq!(p.relate(&Aabb3::new(Point3::new(-1f64, 1f64, -1f64), Point3::new(1f64, 4f64, 1f64))), Cross);
+    assert_eq!(p.relate(&Aabb3::new(Point3::new(-1f64, 0f64, -1f64), Point3::new(1f64, 1f64, 1f64))), Out);
+
+    // the same plane with a normal that is not of unit length
+    let q = Plane::from_points(Point3::new(0f64, 2f64, 0f64), Point3::new(0f64, 2f64, 4f64), Point3::new(4f64, 2f64, 0f64)).unwrap();
+    assert_eq!(q.relate(&Sphere { center: Point3::new(0f64, 4f64, 0f64), radius: 1f64 }), In);
+    assert_eq!(q.relate(&Sphere { center: Point3::new(0f64, 2.5f64, 0f64), radius: 1f64 }), Cross);
+    assert_eq!(q.relate(&Sphere { center: Point3::new(0f64, 0f64, 0f64), radius: 1f64 }), Out);
+}
@@ -0,0 +1,85 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_spatial_hash2() {
+    let mut grid: SpatialHash2<f64, char> = SpatialHash2::new(1f64);
+    let a = grid.insert(&Point2::new(0.5f64, 0.5f64), 'a');
+    let b = grid.insert(&Point2::new(2.5f64, 0.5f64), 'b');
+    let c = grid.insert(&Point2::new(-3.2f64, 4.1f64), 'c');
+    assert_eq!(grid.len(), 3);
+    assert_eq!(*grid.get(c), 'c');
+    assert_eq!(grid.cell(&Point2::new(-3.2f64, 4.1f64)), Point2::new(-4i, 4i));
+
+    assert_eq!(grid.query_radius(&Point2::new(0f64, 0f64), 1f64), vec![a]);
+
+    let nearest = grid.nearest(&Point2::new(2f64, 0.4f64), 2);
+    assert_eq!(nearest.len(), 2);
+    assert_eq!(nearest[0].val1(), b);
+    assert!(nearest[0].val0().approx_eq(&0.26f64.sqrt()));
+    assert_eq!(nearest[1].val1(), a);
+    assert_eq!(grid.nearest(&Point2::new(0f64, 0f64), 5).len(), 3);
+
+    let mut cells = Vec::new();
+    let ray = Ray::new(Point2::new(0.5f64, 0.5f64), Vector2::new(1f64, 0.5f64));
+    grid.traverse_ray(&ray, 3f64, |cell, _| { cells.push(*cell); true });
+    assert_eq!(cells, vec![Point2::new(0i, 0i), Point2::new(1i, 0i), Point2::new(1i, 1i),
+                           Point2::new(2i, 1i), Point2::new(3i, 1i), Point2::new(3i, 2i)]);
+
+    let ray = Ray::new(Point2::new(-0.5f64, 0.5f64), Vector2::unit_x());
+    assert_eq!(grid.query_ray(&ray, 10f64), vec![(1f64, a), (3f64, b)]);
+
+    assert_eq!(grid.remove(a), 'a');
+    assert!(grid.query_radius(&Point2::new(0f64, 0f64), 1f64).is_empty());
+}
+
+#[test]
+fn test_spatial_hash3() {
+    let mut grid: SpatialHash3<f64, uint> = SpatialHash3::new(1f64);
+    let aabb = Aabb3::new(Point3::new(0.5f64, 0.5f64, 0.5f64), Point3::new(2.5f64, 0.7f64, 0.7f64));
+    let id = grid.insert_aabb(&aabb, 0);
+    assert_eq!(grid.objects_in_cell(&Point3::new(1i, 0i, 0i)).unwrap().len(), 1);
+    assert!(grid.objects_in_cell(&Point3::new(1i, 1i, 0i)).is_none());
+    assert_eq!(grid.query_radius(&Point3::new(1.5f64, 2f64, 0.6f64), 1.5f64), vec![id]);
+
+    let ray = Ray::new(Point3::new(1.5f64, 5f64, 0.6f64), Vector3::new(0f64, -1f64, 0f64));
+    let hits = grid.query_ray(&ray, 10f64);
+    assert_eq!(hits.len(), 1);
+    assert!(hits[0].val0().approx_eq(&4.3f64));
+    assert!(grid.query_ray(&ray, 4f64).is_empty());
+
+    assert_eq!(grid.remove(id), 0);
+    assert!(grid.objects_in_cell(&Point3::new(1i, 0i, 0i)).is_none());
+    assert!(grid.is_empty());
+}
+
+#[test]
+fn test_nearest_distant() {
+    // the search should not visit every empty cell between distant objects
+    let mut grid: SpatialHash3<f64, uint> = SpatialHash3::new(1f64);
+    let near = grid.insert(&Point3::new(0.5f64, 0.5f64, 0.5f64), 0);
+    let far = grid.insert(&Point3::new(1e4f64, 0.5f64, 0.5f64), 1);
+    let nearest = grid.nearest(&Point3::new(0f64, 0.5f64, 0.5f64), 2);
+    assert_eq!(nearest.len(), 2);
+    assert_eq!(nearest[0].val1(), near);
+    assert_eq!(nearest[1].val1(), far);
+    assert!(nearest[1].val0().approx_eq(&1e4f64));
+}
@@ -31,3 +31,106 @@ fn test_intersection() {
     assert_eq!((sphere,r2).intersection_point(), Some(Point3::new(1f64, 0f64, 0f64)));
     assert_eq!((sphere,r3).intersection_point(), None);
 }
+
+#[test]
+fn test_ray_intersection_params() {
+    let sphere = Sphere::new(Point3::new(0f64, 0f64, 0f64), 1f64);
+    let outside = Ray::new(Point3::new(0f64, 0f64, 5f64), Vector3::new(0f64, 0f64, -1f64));
+    let inside = Ray::new(Point3::new(0f64, 0f64, 0.5f64), Vector3::new(0f64, 0f64, -1f64));
+    let behind = Ray::new(Point3::new(0f64, 0f64, 5f64), Vector3::new(0f64, 0f64, 1f64));
+    assert_eq!((sphere, outside).intersection(), Some((4f64, 6f64)));
+    assert_eq!((sphere, inside).intersection(), Some((-0.5f64, 1.5f64)));
+    assert_eq!((sphere, behind).intersection(), None);
+    assert_eq!((sphere, inside).intersection_point(), Some(Point3::new(0f64, 0f64, -1f64)));
+}
+
+#[test]
+fn test_ray_intersection_zero_direction() {
+    let sphere = Sphere::new(Point3::new(0f64, 0f64, 0f64), 1f64);
+    let inside = Ray::new(Point3::new(0f64, 0f64, 0.5f64), Vector3::new(0f64, 0f64, 0f64));
+    let outside = Ray::new(Point3::new(0f64, 0f64, 5f64), Vector3::new(0f64, 0f64, 0f64));
+    assert_eq!((sphere, inside).intersection(), None);
+    assert_eq!((sphere, outside).intersection(), None);
+    assert_eq!((sphere, inside).intersection_point(), None);
+}
+
+#[test]
+fn test_overlap() {
+    let sphere = Sphere::new(Point3::new(0f64, 0f64, 0f64), 1f64);
+    assert!(sphere.contains(&Point3::new(0f64, 1f64, 0f64)));
+    assert!(!sphere.contains(&Point3::new(0f64, 1f64, 1f64)));
+
+    assert!((sphere, Sphere::new(Point3::new(1.5f64, 0f64, 0f64), 1f64)).intersection());
+    assert!(!(sphere, Sphere::new(Point3::new(2.5f64, 0f64, 0f64), 1f64)).intersection());
+
+    assert!((sphere, Aabb3::new(Point3::new(0.5f64, 0.5f64, -1f64), Point3::new(2f64, 2f64, 1f64))).intersection());
+    assert!(!(sphere, Aabb3::new(Point3::new(0.8f64, 0.8f64, -1f64), Point3::new(2f64, 2f64, 1f64))).intersection());
+
+    assert!((sphere, Plane::new(Vector3::unit_x(), -0.5f64)).intersection());
+    assert!(!(sphere, Plane::new(Vector3::unit_x(), -1.5f64)).intersection());
+}
+
+#[test]
+fn test_from_points() {
+    let mut points = Vec::new();
+    for i in range(0u, 8) {
+        points.push(Point3::new((i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64));
+    }
+    points.push(Point3::new(0.5f64, 0.5f64, 0.5f64));
+
+    let exact = Sphere::from_points_welzl(points.as_slice()).unwrap();
+    assert!(exact.center.approx_eq(&Point3::new(0.5f64, 0.5f64, 0.5f64)));
+    assert!(exact.radius.approx_eq(&(3f64.sqrt() / 2f64)));
+
+    let approx = Sphere::from_points_ritter(points.as_slice()).unwrap();
+    assert!(approx.radius >= exact.radius - 1e-9f64);
+    for p in points.iter() {
+        assert!(p.sub_p(&approx.center).length() <= approx.radius + 1e-9f64);
+    }
+
+    let single = Sphere::from_points_welzl(&[Point3::new(1f64, 2f64, 3f64)]).unwrap();
+    assert_eq!(single, Sphere::new(Point3::new(1f64, 2f64, 3f64), 0f64));
+    let empty: &[Point3<f64>] = &[];
+    assert_eq!(Sphere::from_points_welzl(empty), None);
+    assert_eq!(Sphere::from_points_ritter(empty), None);
+}
+
+#[test]
+fn test_sweep() {
+    let sphere = Sphere::new(Point3::new(0f64, 0f64, 5f64), 1f64);
+    let down = Vector3::new(0f64, 0f64, -10f64);
+
+    let (t, n) = sphere.sweep_plane(&down, &Plane::new(Vector3::new(0f64, 0f64, 2f64), 0f64)).unwrap();
+    assert!(t.approx_eq(&0.4f64));
+    assert!(n.approx_eq(&Vector3::unit_z()));
+    assert_eq!(sphere.sweep_plane(&down.mul_s(0.2f64), &Plane::new(Vector3::unit_z(), 0f64)), None);
+    assert_eq!(sphere.sweep_plane(&-down, &Plane::new(Vector3::unit_z(), 0f64)), None);
+
+    let (t, n) = sphere.sweep_sphere(&down, &Sphere::new(Point3::new(0f64, 0f64, 0f64), 2f64)).unwrap();
+    assert!(t.approx_eq(&0.2f64));
+    assert!(n.approx_eq(&Vector3::unit_z()));
+    assert_eq!(sphere.sweep_sphere(&down, &Sphere::new(Point3::new(3f64, 0f64, 0f64), 1f64)), None);
+
+    let aabb = Aabb3::new(Point3::new(-1f64, -1f64, -1f64), Point3::new(1f64, 1f64, 1f64));
+    let (t, n) = sphere.sweep_aabb(&down, &aabb).unwrap();
+    assert!(t.approx_eq(&0.3f64));
+    assert!(n.approx_eq(&Vector3::unit_z()));
+    // the sphere passes over an edge of the box, and touches it at 45 degrees
+    let offset = Sphere::new(Point3::new(1f64 + 0.5f64.sqrt(), 0f64, 5f64), 1f64);
+    let (t, n) = offset.sweep_aabb(&down, &aabb).unwrap();
+    assert!(t.approx_eq(&((4f64 - 0.5f64.sqrt()) / 10f64)));
+    assert!(n.approx_eq(&Vector3::new(1f64, 0f64, 1f64).normalize()));
+    assert_eq!(offset.sweep_aabb(&Vector3::new(5f64, 0f64, -10f64), &aabb), None);
+
+    let tri = Triangle3::new(Point3::new(-1f64, -1f64, 0f64), Point3::new(1f64, -1f64, 0f64), Point3::new(0f64, 1f64, 0f64));
+    let (t, n) = sphere.sweep_triangle(&down, &tri).unwrap();
+    assert!(t.approx_eq(&0.4f64));
+    assert!(n.approx_eq(&Vector3::unit_z()));
+    let (t, n) = Sphere::new(Point3::new(0f64, 1.5f64, 5f64), 1f64).sweep_triangle(&down, &tri).unwrap();
+    assert!(t > 0.4f64 && t < 0.5f64);
+    assert!(n.y > 0f64 && n.z > 0f64);
+    assert_eq!(Sphere::new(Point3::new(5f64, 0f64, 5f64), 1f64).sweep_triangle(&down, &tri), None);
+
+    let touching = Sphere::new(Point3::new(0f64, 0f64, 0.5f64), 1f64);
+    assert_eq!(touching.sweep_triangle(&down, &tri).map(|(t, _)| t), Some(0f64));
+}
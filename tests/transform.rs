@@ -42,3 +42,156 @@ fn test_look_at() {
 	let view_point = Point3::new(0.0f64, 1.0f64, 5.0f64);
 	assert!( t.transform_point(&point).approx_eq(&view_point) );
 }
+
+#[test]
+fn test_transform_bounds() {
+	let rot: Quaternion<f64> = Rotation3::from_angle_z(deg(90f64).to_rad());
+	let t = Decomposed {
+		scale: 2.0f64,
+		rot: rot,
+		disp: Vector3::new(1.0f64, 2.0, 3.0),
+	};
+
+	let aabb = Aabb3::new(Point3::new(0.0f64, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0)).transform(&t);
+	assert!(aabb.min.approx_eq(&Point3::new(-3.0, 2.0, 3.0)));
+	assert!(aabb.max.approx_eq(&Point3::new(1.0, 4.0, 9.0)));
+
+	let sphere = Sphere::new(Point3::new(1.0f64, 0.0, 0.0), 1.0).transform(&t);
+	assert!(sphere.center.approx_eq(&Point3::new(1.0, 4.0, 3.0)));
+	assert!(sphere.radius.approx_eq(&2.0));
+
+	let obb = Obb3::from_aabb3(&Aabb3::new(Point3::new(-1.0f64, -1.0, -1.0), Point3::new(1.0, 1.0, 2.0))).transform(&t);
+	assert!(obb.center.approx_eq(&Point3::new(1.0, 2.0, 4.0)));
+	assert!(obb.extents.approx_eq(&Vector3::new(2.0, 2.0, 3.0)));
+	assert!(obb.axes()[0].approx_eq(&Vector3::new(0.0, 1.0, 0.0)));
+
+	// the plane z = 1 is moved to z = 5
+	let plane = Plane::from_abcd(0.0f64, 0.0, 1.0, -1.0).transform(&t).unwrap();
+	assert!(plane.n.approx_eq(&Vector3::new(0.0, 0.0, 1.0)));
+	assert!(plane.d.approx_eq(&-5.0));
+}
+
+#[test]
+fn test_transform_bounds_nonuniform() {
+	let m = Matrix3::new(2.0f64, 0.0, 0.0,
+	                     0.0, 3.0, 0.0,
+	                     0.0, 0.0, 1.0).to_matrix4();
+
+	let sphere = Sphere::new(Point3::new(1.0f64, 1.0, 1.0), 1.0).transform_matrix4(&m);
+	assert!(sphere.center.approx_eq(&Point3::new(2.0, 3.0, 1.0)));
+	assert!(sphere.radius.approx_eq(&3.0));
+
+	// the plane x + y = 0 becomes 3x + 2y = 0, and keeps the length of its normal
+	let plane = Plane::from_abcd(1.0f64, 1.0, 0.0, 0.0).transform_matrix4(&m).unwrap();
+	assert!(plane.distance(&Point3::new(2.0, -3.0, 7.0)).approx_eq(&0.0));
+	assert!(plane.n.length().approx_eq(&2.0f64.sqrt()));
+
+	// a rotated box is no longer axis-aligned after the scale
+	let rot: Basis3<f64> = Rotation3::from_angle_z(deg(45f64).to_rad());
+	let obb = Obb3::new(Point3::new(0.0f64, 0.0, 0.0), rot, Vector3::new(1.0, 1.0, 1.0)).transform_matrix4(&m);
+	let aabb = obb.to_aabb3();
+	let corners = Obb3::new(Point3::new(0.0f64, 0.0, 0.0), rot, Vector3::new(1.0, 1.0, 1.0)).corners();
+	for c in corners.iter() {
+		let p = Point3::new(2.0 * c.x, 3.0 * c.y, c.z);
+		assert!(aabb.grow(&p).min.approx_eq(&aabb.min) && aabb.grow(&p).max.approx_eq(&aabb.max));
+	}
+}
+
+#[test]
+fn test_decompose() {
+	let rot: Quaternion<f64> = Rotation3::from_axis_angle(&Vector3::new(1.0f64, 2.0, 3.0).normalize(), rad(0.7f64));
+	let t = Decomposed {
+		scale: 1.5f64,
+		rot: rot,
+		disp: Vector3::new(6.0f64, -7.0, 8.0),
+	};
+	let d = Decomposed::from_matrix4(&t.to_matrix4()).ok().expect("Expected successful decomposition");
+	assert!(d.scale.approx_eq(&1.5));
+	assert!(d.disp.approx_eq(&t.disp));
+	assert!(d.to_matrix4().approx_eq(&t.to_matrix4()));
+
+	// a homogeneous matrix may be scaled by any non-zero amount
+	let d = AffineMatrix3 { mat: t.to_matrix4().mul_s(2.0) }.decompose().ok().unwrap();
+	assert!(d.to_matrix4().approx_eq(&t.to_matrix4()));
+
+	// a mirror image has a negative scale
+	let mirror = Matrix3::from_diagonal(&Vector3::new(-2.0f64, 2.0, 2.0)).to_matrix4();
+	let d = Decomposed::from_matrix4(&mirror).ok().unwrap();
+	assert!(d.scale.approx_eq(&-2.0));
+	assert!(d.to_matrix4().approx_eq(&mirror));
+}
+
+#[test]
+fn test_decompose_errors() {
+	let scale = Matrix3::from_diagonal(&Vector3::new(2.0f64, 3.0, 1.0)).to_matrix4();
+	assert!(Decomposed::from_matrix4(&scale).err() == Some(NonUniformScale(Vector3::new(2.0, 3.0, 1.0))));
+
+	let shear = Matrix3::new(1.0f64, 0.0, 0.0,
+	                         1.0, 1.0, 0.0,
+	                         0.0, 0.0, 1.0).to_matrix4();
+	assert!(Decomposed::from_matrix4(&shear).err() == Some(ShearedMatrix));
+
+	let flat = Matrix3::from_diagonal(&Vector3::new(1.0f64, 1.0, 0.0)).to_matrix4();
+	assert!(Decomposed::from_matrix4(&flat).err() == Some(SingularMatrix));
+	assert!(Decomposed::from_matrix4(&Matrix3::zero().to_matrix4()).err() == Some(SingularMatrix));
+
+	let proj = perspective(deg(60.0f64), 1.0, 0.1, 100.0);
+	assert!(Decomposed::from_matrix4(&proj).err() == Some(PerspectiveMatrix));
+}
+
+#[test]
+fn test_non_uniform() {
+	let rot: Quaternion<f64> = Rotation3::from_axis_angle(&Vector3::new(1.0f64, 2.0, 3.0).normalize(), rad(0.7f64));
+	let t = NonUniformDecomposed {
+		scale: Vector3::new(2.0f64, 3.0, -0.5),
+		rot: rot,
+		disp: Vector3::new(6.0f64, -7.0, 8.0),
+	};
+	let m = t.to_matrix4();
+	let p = Point3::new(1.0f64, 2.0, 3.0);
+	assert!(t.transform_point(&p).approx_eq(&Point3::from_homogeneous(&m.mul_v(&p.to_homogeneous()))));
+
+	let d = NonUniformDecomposed::from_matrix4(&m).ok().expect("Expected successful decomposition");
+	assert!(d.to_matrix4().approx_eq(&m));
+	match Decomposed::from_matrix4(&m) {
+		Err(NonUniformScale(scale)) => assert!(scale.approx_eq(&Vector3::new(2.0, 3.0, -0.5))),
+		_ => fail!("Expected a non-uniform scale"),
+	}
+}
+
+#[test]
+fn test_non_uniform_concat_invert() {
+	let quarter: Quaternion<f64> = Rotation3::from_angle_z(deg(90f64).to_rad());
+	let a = NonUniformDecomposed {
+		scale: Vector3::new(2.0f64, 3.0, 4.0),
+		rot: Rotation::identity(),
+		disp: Vector3::new(1.0f64, 0.0, 0.0),
+	};
+	let b = NonUniformDecomposed {
+		scale: Vector3::new(1.0f64, 0.5, 1.0),
+		rot: quarter,
+		disp: Vector3::new(0.0f64, 2.0, 0.0),
+	};
+
+	// rotating by a quarter turn keeps the axes aligned, so these are exact
+	assert!(a.concat(&b).to_matrix4().approx_eq(&a.to_matrix4().mul_m(&b.to_matrix4())));
+	assert!(b.concat(&a).to_matrix4().approx_eq(&b.to_matrix4().mul_m(&a.to_matrix4())));
+	let inv = b.invert().unwrap();
+	assert!(inv.to_matrix4().approx_eq(&b.to_matrix4().invert().unwrap()));
+
+	// an eighth of a turn shears the scaled axes, so only the origin is exact
+	let eighth: Quaternion<f64> = Rotation3::from_angle_z(deg(45f64).to_rad());
+	let c = NonUniformDecomposed {
+		scale: Vector3::new(1.0f64, 2.0, 1.0),
+		rot: eighth,
+		disp: Vector3::new(1.0f64, 2.0, 3.0),
+	};
+	let inv = c.invert().unwrap();
+	let exact = AffineMatrix3 { mat: c.to_matrix4().invert().unwrap() };
+	let origin = Point3::new(0.0f64, 0.0, 0.0);
+	assert!(inv.transform_point(&origin).approx_eq(&exact.transform_point(&origin)));
+	assert!(inv.scale.approx_eq(&Vector3::new(0.75, 0.75, 1.0)));
+
+	let singular = NonUniformDecomposed { scale: Vector3::new(1.0f64, 0.0, 1.0), rot: quarter, disp: Vector3::zero() };
+	assert!(singular.invert().is_none());
+}
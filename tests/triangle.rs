@@ -0,0 +1,68 @@
+// Copyright 2014 The CGMath Developers. For a full listing of the authors,
+// refer to the AUTHORS file at the top-level directory of this distribution.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![feature(globs)]
+
+extern crate cgmath;
+
+use cgmath::*;
+
+#[test]
+fn test_triangle2() {
+    let tri = Triangle2::new(Point2::new(0f64, 0f64), Point2::new(2f64, 0f64), Point2::new(0f64, 2f64));
+    assert_eq!(tri.signed_area(), 2f64);
+    assert_eq!(Triangle2::new(tri.a, tri.c, tri.b).signed_area(), -2f64);
+    assert_eq!(tri.area(), 2f64);
+
+    assert!(tri.contains(&Point2::new(0.5f64, 0.5f64)));
+    assert!(!tri.contains(&Point2::new(2f64, 2f64)));
+    assert_eq!(tri.closest_point(&Point2::new(2f64, 2f64)), Point2::new(1f64, 1f64));
+    assert_eq!(tri.closest_point(&Point2::new(-1f64, -3f64)), Point2::new(0f64, 0f64));
+}
+
+#[test]
+fn test_triangle3() {
+    let tri = Triangle3::new(Point3::new(0f64, 0f64, 0f64),
+                             Point3::new(1f64, 0f64, 0f64),
+                             Point3::new(0f64, 1f64, 0f64));
+    assert_eq!(tri.normal(), Vector3::new(0f64, 0f64, 1f64));
+    assert_eq!(tri.area(), 0.5f64);
+    assert!(tri.centroid().approx_eq(&Point3::new(1f64 / 3f64, 1f64 / 3f64, 0f64)));
+    assert_eq!(tri.barycentric(&Point3::new(0.25f64, 0.25f64, 0f64)), (0.5f64, 0.25f64, 0.25f64));
+
+    // the closest point may be on a corner, an edge or the face
+    assert_eq!(tri.closest_point(&Point3::new(-1f64, -1f64, 0f64)), Point3::new(0f64, 0f64, 0f64));
+    assert_eq!(tri.closest_point(&Point3::new(2f64, 2f64, 1f64)), Point3::new(0.5f64, 0.5f64, 0f64));
+    assert_eq!(tri.closest_point(&Point3::new(0.25f64, 0.25f64, 5f64)), Point3::new(0.25f64, 0.25f64, 0f64));
+}
+
+#[test]
+fn test_triangle3_ray() {
+    let tri = Triangle3::new(Point3::new(0f64, 0f64, 0f64),
+                             Point3::new(1f64, 0f64, 0f64),
+                             Point3::new(0f64, 1f64, 0f64));
+    let front = Ray::new(Point3::new(0.25f64, 0.25f64, 1f64), Vector3::new(0f64, 0f64, -1f64));
+    let back = Ray::new(Point3::new(0.25f64, 0.25f64, -1f64), Vector3::new(0f64, 0f64, 1f64));
+    let away = Ray::new(Point3::new(0.25f64, 0.25f64, -1f64), Vector3::new(0f64, 0f64, -1f64));
+    let outside = Ray::new(Point3::new(1f64, 1f64, 1f64), Vector3::new(0f64, 0f64, -1f64));
+
+    assert_eq!((tri, front).intersection(), Some((1f64, 0.25f64, 0.25f64)));
+    assert_eq!((tri, front).intersection_point(), Some(Point3::new(0.25f64, 0.25f64, 0f64)));
+    assert_eq!((tri, back).intersection(), Some((1f64, 0.25f64, 0.25f64)));
+    assert_eq!(tri.intersect_ray(&front, true), Some((1f64, 0.25f64, 0.25f64)));
+    assert_eq!(tri.intersect_ray(&back, true), None);
+    assert_eq!((tri, away).intersection(), None);
+    assert_eq!((tri, outside).intersection(), None);
+}